@@ -0,0 +1,126 @@
+#ifndef CADOM_H
+#define CADOM_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Opaque handle of [Decay] owned by foreign code.
+ */
+typedef struct CadomDecay CadomDecay;
+
+/**
+ * Borrowed UTF-8 text, which is not NUL-terminated. Null pointer stands for the absent text.
+ */
+typedef struct CadomStr {
+  const char *ptr;
+  size_t len;
+} CadomStr;
+
+/**
+ * Called for every level, from the outermost one to the root one, with the note of the level
+ * (or the message of the foreign error for the external level). May be null.
+ */
+typedef void (*CadomNoteVisitor)(void *context, size_t level, struct CadomStr note);
+
+/**
+ * Called for every place of every level, in the same order as places are rendered. May be null.
+ */
+typedef void (*CadomPlaceVisitor)(void *context,
+                                  size_t level,
+                                  struct CadomStr file,
+                                  uint32_t line,
+                                  uint32_t column);
+
+/**
+ * Creates the internal decay. The note may be null.
+ *
+ * # Safety
+ * Non-null string pointers should point to valid NUL-terminated strings.
+ */
+struct CadomDecay *cadom_decay_new(const char *file,
+                                   uint32_t line,
+                                   uint32_t column,
+                                   const char *note);
+
+/**
+ * Creates the external decay from the foreign error message.
+ *
+ * # Safety
+ * Non-null message pointer should point to the valid NUL-terminated string.
+ */
+struct CadomDecay *cadom_decay_from_error(const char *message);
+
+/**
+ * Appends the further level (just like `rot!` does) and returns the resulting decay.
+ * The given handle is consumed and should not be used anymore. The note may be null.
+ *
+ * # Safety
+ * The handle should be the valid one returned by this library; non-null string pointers
+ * should point to valid NUL-terminated strings.
+ */
+struct CadomDecay *cadom_decay_further(struct CadomDecay *decay,
+                                       const char *file,
+                                       uint32_t line,
+                                       uint32_t column,
+                                       const char *note);
+
+/**
+ * Returns the amount of levels (zero for the null handle).
+ *
+ * # Safety
+ * The handle should be either null or the valid one returned by this library.
+ */
+size_t cadom_decay_depth(const struct CadomDecay *decay);
+
+/**
+ * Calls the visitor for the note of every level. Texts are valid during the call only.
+ * Nothing is done if either the handle or the visitor is null.
+ *
+ * # Safety
+ * The handle should be either null or the valid one returned by this library.
+ */
+void cadom_decay_visit_notes(const struct CadomDecay *decay,
+                             CadomNoteVisitor visitor,
+                             void *context);
+
+/**
+ * Calls the visitor for every place of every level. Texts are valid during the call only.
+ * Nothing is done if either the handle or the visitor is null.
+ *
+ * # Safety
+ * The handle should be either null or the valid one returned by this library.
+ */
+void cadom_decay_visit_places(const struct CadomDecay *decay,
+                              CadomPlaceVisitor visitor,
+                              void *context);
+
+/**
+ * Renders the decay with the process-wide format (see `DecayFormat::set_global`).
+ * The result should be released with [cadom_string_free]; null is returned for the null handle.
+ *
+ * # Safety
+ * The handle should be either null or the valid one returned by this library.
+ */
+char *cadom_decay_render(const struct CadomDecay *decay);
+
+/**
+ * Releases the string returned by [cadom_decay_render]. Null is ignored.
+ *
+ * # Safety
+ * The string should be either null or the one returned by this library and not released yet.
+ */
+void cadom_string_free(char *text);
+
+/**
+ * Releases the decay. Null is ignored.
+ *
+ * # Safety
+ * The handle should be either null or the valid one returned by this library and not released yet.
+ */
+void cadom_decay_free(struct CadomDecay *decay);
+
+#endif  /* CADOM_H */
@@ -0,0 +1,155 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::Parser,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Block, Expr, ExprBlock, ExprCall, ExprIf, ExprLit, ExprMatch, ExprParen, ExprPath, ExprReturn,
+    ExprTry, Item, ItemFn, Lit, Stmt, Token,
+};
+
+pub fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut function: ItemFn = syn::parse2(item)?;
+    let note = parse_note(args)?;
+    let mut rewriter = Rewriter {
+        function: function.sig.ident.to_string(),
+        note,
+    };
+    rewriter.visit_block_mut(&mut function.block);
+    rewriter.visit_tail_mut(&mut function.block);
+    Ok(quote!(#function))
+}
+
+fn parse_note(args: TokenStream) -> syn::Result<Option<TokenStream>> {
+    let args = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(args)?;
+    let template = match args.first() {
+        None => return Ok(None),
+        Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(template),
+            ..
+        })) => template,
+        Some(other) => {
+            return Err(syn::Error::new(
+                other.span(),
+                "note template should be a string literal",
+            ))
+        }
+    };
+    Ok(Some(
+        match args.len() == 1 && !template.value().contains('{') {
+            true => quote!(#template),
+            false => quote!(::std::format!(#args)),
+        },
+    ))
+}
+
+struct Rewriter {
+    function: String,
+    note: Option<TokenStream>,
+}
+
+impl Rewriter {
+    fn place(&self, span: Span) -> TokenStream {
+        let function = &self.function;
+        quote_spanned! {span=>
+            ::cadom::CodePlace::new(::std::file!(), ::std::line!(), ::std::column!())
+                .with_module(::cadom::__place_module!())
+                .with_function(::std::option::Option::Some(#function))
+                .with_crate(::cadom::__place_crate!())
+        }
+    }
+
+    fn morph(&self, span: Span) -> TokenStream {
+        let place = self.place(span);
+        match &self.note {
+            None => quote!(::cadom::Decay::morph_unnoted(#place)),
+            Some(note) => quote! {
+                |error| ::cadom::Decay::morph(#place, #note)(error)
+            },
+        }
+    }
+
+    fn morph_err(&self, call: &mut ExprCall, span: Span) {
+        let morph = self.morph(span);
+        let error = &call.args[0];
+        call.args[0] = syn::parse_quote!((#morph)(#error));
+    }
+
+    /// Handles `Err(..)` the block evaluates to, looking through nested blocks, `if` and `match`.
+    fn visit_tail_mut(&self, block: &mut Block) {
+        if let Some(Stmt::Expr(tail, None)) = block.stmts.last_mut() {
+            self.visit_tail_expr_mut(tail);
+        }
+    }
+
+    fn visit_tail_expr_mut(&self, expr: &mut Expr) {
+        match expr {
+            Expr::Call(call) if is_err_call(call) => {
+                let span = call.func.span();
+                self.morph_err(call, span);
+            }
+            Expr::Block(ExprBlock { block, .. }) => self.visit_tail_mut(block),
+            Expr::If(ExprIf {
+                then_branch,
+                else_branch,
+                ..
+            }) => {
+                self.visit_tail_mut(then_branch);
+                if let Some((_, other)) = else_branch {
+                    self.visit_tail_expr_mut(other);
+                }
+            }
+            Expr::Match(ExprMatch { arms, .. }) => arms
+                .iter_mut()
+                .for_each(|arm| self.visit_tail_expr_mut(&mut arm.body)),
+            Expr::Paren(ExprParen { expr, .. }) => self.visit_tail_expr_mut(expr),
+            _ => {}
+        }
+    }
+}
+
+fn is_err_call(call: &ExprCall) -> bool {
+    match call.func.as_ref() {
+        Expr::Path(ExprPath {
+            path, qself: None, ..
+        }) => call.args.len() == 1 && path.segments.last().is_some_and(|s| s.ident == "Err"),
+        _ => false,
+    }
+}
+
+impl VisitMut for Rewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        match expr {
+            Expr::Try(ExprTry {
+                expr: inner,
+                question_token,
+                ..
+            }) => {
+                let morph = self.morph(question_token.span());
+                **inner = syn::parse_quote! {
+                    ::std::result::Result::map_err(#inner, #morph)
+                };
+            }
+            Expr::Return(ExprReturn {
+                expr: Some(returned),
+                return_token,
+                ..
+            }) => {
+                if let Expr::Call(call) = returned.as_mut() {
+                    if is_err_call(call) {
+                        self.morph_err(call, return_token.span());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_expr_closure_mut(&mut self, _: &mut syn::ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut syn::ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
@@ -0,0 +1,58 @@
+use proc_macro::TokenStream;
+
+mod instrument;
+mod note;
+mod origin;
+
+/// Rewrites the function so every error leaving it through `?`, `return Err(..)` or `Err(..)`
+/// as the function tail (looking through blocks, `if` and `match`) gets the place of that exit
+/// (and, optionally, the note) added, just like `rot!` does.
+/// The function should return `Result<_, cadom::Decay<_>>`.
+///
+/// The note template and its arguments (if any) are given the same way as for `rot!`,
+/// e.g. `#[cadom::instrument("Can't load '{}'", path)]`. The note is formatted only
+/// when an error actually leaves the function, so the arguments should be still alive
+/// (not moved out) at every exit point.
+///
+/// Nested closures, async blocks and items are left untouched, as well as the
+/// contents of macro invocations.
+#[proc_macro_attribute]
+pub fn instrument(args: TokenStream, item: TokenStream) -> TokenStream {
+    instrument::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `cadom::Origin` for the enum, using snake-cased variant names as codes,
+/// and generates the boilerplate usually written around the origin error type.
+///
+/// Container attributes:
+/// - `#[origin(alias = Fail)]` declares `type Fail = cadom::Decay<Self>` with the enum visibility;
+/// - `#[origin(serde)]` and `#[origin(schema)]` assert at compile time that `cadom::Decay<Self>`
+///   implements `Serialize` and `JsonSchema` respectively (checked for non-generic enums only
+///   and require the corresponding features of cadom).
+///
+/// Variant attributes:
+/// - `#[origin(code = "...")]` overrides the classification code;
+/// - `#[origin(from)]` implements `From` of the only unnamed field type;
+/// - `#[origin(text)]` implements `From<String>` and `From<&str>` through the only unnamed field.
+#[proc_macro_derive(Origin, attributes(origin))]
+pub fn derive_origin(input: TokenStream) -> TokenStream {
+    syn::parse(input)
+        .and_then(origin::expand)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Makes `Note::templated` out of the format string and its arguments, for `note!`, `decay!`
+/// and `rot!`. Every argument is evaluated once and bound to its own (hygienic) name, which is
+/// then used both for the rendered text and for the argument representation, formatted the same
+/// way the format string formats the argument first. Positional arguments are recorded by index,
+/// names captured by the format string implicitly (like `{id}`) are passed as named arguments.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __templated_note(input: TokenStream) -> TokenStream {
+    note::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,242 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, ExprLit, Ident, Lit, LitStr, Token,
+};
+
+/// Input of `__templated_note!`: the path of cadom (`$crate` of the calling macro),
+/// the format string and its arguments, just like `format!` takes them.
+struct TemplatedNote {
+    krate: TokenTree,
+    format: Expr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for TemplatedNote {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let krate = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let format = input.parse()?;
+        let args = match input.is_empty() {
+            true => Punctuated::new(),
+            false => {
+                input.parse::<Token![,]>()?;
+                Punctuated::parse_terminated(input)?
+            }
+        };
+        Ok(Self {
+            krate,
+            format,
+            args,
+        })
+    }
+}
+
+/// Argument given to the format string, or captured by it implicitly.
+struct FormatArg {
+    /// Key the argument is recorded with: its index for positional arguments, its name otherwise.
+    key: ArgRef,
+    value: TokenStream,
+    /// Name the argument is passed to `format!` with (positional arguments have none).
+    named: Option<Ident>,
+}
+
+/// Reference to the argument made by the format string.
+#[derive(Clone, PartialEq)]
+enum ArgRef {
+    Index(usize),
+    Name(String),
+}
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let TemplatedNote {
+        krate,
+        format,
+        args,
+    } = syn::parse2(input)?;
+    let mut format_args = Vec::new();
+    let mut positional = 0;
+    for arg in args {
+        match arg {
+            Expr::Assign(assign) => {
+                let name = match &*assign.left {
+                    Expr::Path(path) => path.path.get_ident().cloned(),
+                    _ => None,
+                }
+                .ok_or_else(|| syn::Error::new_spanned(&assign.left, "Expected argument name"))?;
+                let value = &assign.right;
+                format_args.push(FormatArg {
+                    key: ArgRef::Name(name.to_string()),
+                    value: quote!(#value),
+                    named: Some(name),
+                });
+            }
+            value => {
+                format_args.push(FormatArg {
+                    key: ArgRef::Index(positional),
+                    value: quote!(#value),
+                    named: None,
+                });
+                positional += 1;
+            }
+        }
+    }
+    let placeholders = template_of(&format)
+        .map(|template| {
+            let placeholders = placeholders(&template.value());
+            for (arg, _) in &placeholders {
+                if let ArgRef::Name(name) = arg {
+                    if !format_args.iter().any(|known| known.key == *arg) {
+                        let name = Ident::new(name, template.span());
+                        format_args.push(FormatArg {
+                            key: arg.clone(),
+                            value: quote!(#name),
+                            named: Some(name),
+                        });
+                    }
+                }
+            }
+            placeholders
+        })
+        .unwrap_or_default();
+
+    let bound = (0..format_args.len())
+        .map(|index| format_ident!("arg{}", index, span = Span::mixed_site()))
+        .collect::<Vec<_>>();
+    let values = format_args.iter().map(|arg| &arg.value);
+    let passed = format_args
+        .iter()
+        .zip(&bound)
+        .map(|(arg, bound)| match &arg.named {
+            Some(name) => quote!(#name = *#bound),
+            None => quote!(*#bound),
+        });
+    // Every argument is recorded the way the format string formats it first, so the
+    // representation needs nothing beyond what the rendered text already requires.
+    let recorded = format_args.iter().zip(&bound).filter_map(|(arg, bound)| {
+        let spec = placeholders
+            .iter()
+            .find_map(|(known, spec)| (*known == arg.key).then_some(spec))?;
+        let key = match &arg.key {
+            ArgRef::Index(index) => index.to_string(),
+            ArgRef::Name(name) => name.clone(),
+        };
+        let spec = LitStr::new(&format!("{{:{}}}", spec), Span::call_site());
+        Some(quote!((#key, ::std::format!(#spec, *#bound))))
+    });
+    Ok(quote! {
+        match (#(&#values,)*) {
+            (#(#bound,)*) => #krate::Note::templated(
+                #format,
+                #krate::NoteArgs::from([#(#recorded),*]),
+                ::std::format!(#format, #(#passed),*),
+            ),
+        }
+    })
+}
+
+/// Returns the format string literal, if it is given as a literal (possibly wrapped into
+/// the invisible group by the calling macro).
+fn template_of(format: &Expr) -> Option<&LitStr> {
+    match format {
+        Expr::Group(group) => template_of(&group.expr),
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(template),
+            ..
+        }) => Some(template),
+        _ => None,
+    }
+}
+
+/// Returns arguments the format string refers to, in the order of appearance, along with
+/// the part of the spec choosing the formatting trait (see [trait_spec]). Arguments used
+/// as widths or precisions (`{:name$}`, `{:.1$}`, `{:.*}`) are formatted with [Display].
+///
+/// [Display]: std::fmt::Display
+fn placeholders(template: &str) -> Vec<(ArgRef, String)> {
+    let mut placeholders = Vec::new();
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+            }
+            '{' => {
+                let body = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+                let (argument, spec) = body.split_once(':').unwrap_or((&body, ""));
+                // The precision given as `.*` takes the positional argument before the value.
+                let precision = spec.contains(".*").then(|| {
+                    next += 1;
+                    ArgRef::Index(next - 1)
+                });
+                let argument = argument.trim();
+                let value = match argument.is_empty() {
+                    true => {
+                        next += 1;
+                        Some(ArgRef::Index(next - 1))
+                    }
+                    false => arg_ref(argument),
+                };
+                placeholders.extend(value.map(|arg| (arg, trait_spec(spec))));
+                let counts = spec.match_indices('$').filter_map(|(index, _)| {
+                    let head = &spec[..index];
+                    let start = head
+                        .char_indices()
+                        .rev()
+                        .find(|(_, c)| !c.is_alphanumeric() && *c != '_')
+                        .map_or(0, |(index, c)| index + c.len_utf8());
+                    arg_ref(&head[start..])
+                });
+                placeholders.extend(counts.chain(precision).map(|arg| (arg, String::new())));
+            }
+            _ => {}
+        }
+    }
+    placeholders
+}
+
+fn arg_ref(text: &str) -> Option<ArgRef> {
+    match text.parse() {
+        Ok(index) => Some(ArgRef::Index(index)),
+        Err(_) if is_identifier(text) => Some(ArgRef::Name(text.to_owned())),
+        Err(_) => None,
+    }
+}
+
+/// Returns the part of the format spec choosing the formatting trait, along with the `#` flag:
+/// nothing for [Display], `?` for [Debug], `#x` for [LowerHex] with the `0x` prefix and so on.
+///
+/// [Display]: std::fmt::Display
+/// [Debug]: std::fmt::Debug
+/// [LowerHex]: std::fmt::LowerHex
+fn trait_spec(spec: &str) -> String {
+    const KINDS: [&str; 10] = ["x?", "X?", "?", "x", "X", "o", "b", "e", "E", "p"];
+
+    let flags = match spec.char_indices().nth(1) {
+        Some((index, '<' | '^' | '>')) => &spec[index + 1..],
+        _ => spec.strip_prefix(['<', '^', '>']).unwrap_or(spec),
+    };
+    let flags = flags.strip_prefix(['+', '-']).unwrap_or(flags);
+    let kind = KINDS
+        .iter()
+        .find(|kind| spec.ends_with(*kind))
+        .unwrap_or(&"");
+    match flags.starts_with('#') {
+        true => format!("#{}", kind),
+        false => kind.to_string(),
+    }
+}
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => {
+            (first.is_alphabetic() || first == '_')
+                && text != "_"
+                && chars.all(|c| c.is_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,210 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, GenericParam, Ident, LitStr, Type};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Origin can be derived for enums only",
+            ))
+        }
+    };
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let name = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut code_arms = Vec::new();
+    let mut conversions = Vec::new();
+    for variant in variants {
+        let attrs = VariantAttrs::parse(&variant.attrs)?;
+        let ident = &variant.ident;
+        let code = attrs
+            .code
+            .unwrap_or_else(|| LitStr::new(&snake_case(ident), ident.span()));
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote!(#name::#ident { .. }),
+            Fields::Unnamed(_) => quote!(#name::#ident(..)),
+            Fields::Unit => quote!(#name::#ident),
+        };
+        code_arms.push(quote!(#pattern => #code,));
+
+        if !(attrs.from || attrs.text) {
+            continue;
+        }
+        let field_ty = match single_field(&variant.fields) {
+            Some(field_ty) => field_ty,
+            None => return Err(syn::Error::new(
+                ident.span(),
+                "`from` and `text` can be used only for variants with exactly one unnamed field",
+            )),
+        };
+        if attrs.from {
+            conversions.push(quote! {
+                impl #impl_generics ::std::convert::From<#field_ty> for #name #ty_generics #where_clause {
+                    fn from(src: #field_ty) -> Self {
+                        #name::#ident(src)
+                    }
+                }
+            });
+        }
+        if attrs.text {
+            conversions.push(quote! {
+                impl #impl_generics ::std::convert::From<::std::string::String> for #name #ty_generics #where_clause {
+                    fn from(src: ::std::string::String) -> Self {
+                        #name::#ident(::std::convert::From::from(src))
+                    }
+                }
+
+                impl #impl_generics ::std::convert::From<&str> for #name #ty_generics #where_clause {
+                    fn from(src: &str) -> Self {
+                        #name::#ident(::std::convert::From::from(src))
+                    }
+                }
+            });
+        }
+    }
+
+    let alias = container.alias.map(|alias| {
+        let params = input.generics.params.iter().map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Const(param) => {
+                let (ident, ty) = (&param.ident, &param.ty);
+                quote!(const #ident: #ty)
+            }
+        });
+        quote! {
+            #vis type #alias<#(#params),*> = ::cadom::Decay<#name #ty_generics>;
+        }
+    });
+
+    let assertions = [
+        (
+            container.serde,
+            quote!(::cadom::__private::serde::Serialize),
+        ),
+        (
+            container.schema,
+            quote!(::cadom::__private::schemars::JsonSchema),
+        ),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled && input.generics.params.is_empty())
+    .map(|(_, bound)| {
+        quote! {
+            const _: fn() = || {
+                fn assert_bound<T: ?::std::marker::Sized + #bound>() {}
+                assert_bound::<::cadom::Decay<#name>>();
+            };
+        }
+    })
+    .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::cadom::Origin for #name #ty_generics #where_clause {
+            fn code(&self) -> &'static str {
+                match self {
+                    #(#code_arms)*
+                }
+            }
+        }
+
+        #(#conversions)*
+
+        #alias
+
+        #(#assertions)*
+    })
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    alias: Option<Ident>,
+    serde: bool,
+    schema: bool,
+}
+
+impl ContainerAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("origin")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("alias") {
+                    result.alias = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("serde") {
+                    result.serde = true;
+                } else if meta.path.is_ident("schema") {
+                    result.schema = true;
+                } else {
+                    return Err(meta.error("expected `alias`, `serde` or `schema`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Default)]
+struct VariantAttrs {
+    code: Option<LitStr>,
+    from: bool,
+    text: bool,
+}
+
+impl VariantAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("origin")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("code") {
+                    result.code = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("from") {
+                    result.from = true;
+                } else if meta.path.is_ident("text") {
+                    result.text = true;
+                } else {
+                    return Err(meta.error("expected `code`, `from` or `text`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+fn single_field(fields: &Fields) -> Option<&Type> {
+    match fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0].ty),
+        _ => None,
+    }
+}
+
+fn snake_case(ident: &Ident) -> String {
+    let chars = ident.to_string().chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(chars.len() + 4);
+    for (index, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
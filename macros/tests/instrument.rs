@@ -0,0 +1,140 @@
+use cadom::{CodePlace, Decay};
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+enum FailKind {
+    #[error(transparent)]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl From<String> for FailKind {
+    fn from(src: String) -> Self {
+        FailKind::Custom(src)
+    }
+}
+
+type Fail = Decay<FailKind>;
+
+#[cadom::instrument]
+fn parse_u8(text: &str) -> Result<u8, Fail> {
+    let value = text.parse::<u8>()?;
+    Ok(value)
+}
+
+#[cadom::instrument("Can't parse '{}' twice", text)]
+fn parse_u8_twice(text: &str) -> Result<u8, Fail> {
+    let value = parse_u8(text)?;
+    if value > 100 {
+        return Err(format!("Value {} is too big", value));
+    }
+    let halve = |text: &str| -> Result<u8, std::num::ParseIntError> { Ok(text.parse::<u8>()? / 2) };
+    Ok(halve(text).map_or(value, |half| half * 2))
+}
+
+#[cadom::instrument("Can't check {}", value)]
+fn check_even(value: u8) -> Result<u8, Fail> {
+    match value % 2 {
+        0 => Ok(value),
+        _ => {
+            let error = format!("Value {} is odd", value);
+            Err(error)
+        }
+    }
+}
+
+/// Returns the line and the column (both 1-based) of the token inside the first occurrence
+/// of the code fragment in this file, which is where the instrumented function exit is expected.
+fn position_of(fragment: &str, token: &str) -> (u32, u32) {
+    include_str!("instrument.rs")
+        .lines()
+        .zip(1..)
+        .find_map(|(text, line)| {
+            let start = text.find(fragment)?;
+            let offset = fragment.find(token)?;
+            Some((line, (start + offset + 1) as u32))
+        })
+        .expect("Fragment should be present")
+}
+
+fn places(chain: &cadom::CodePlaceChain) -> Vec<(u32, u32, Option<&'static str>)> {
+    chain
+        .iter()
+        .map(|place: &CodePlace| (place.line, place.column, place.function()))
+        .collect()
+}
+
+#[test]
+fn question_mark_unnoted() {
+    let fail = parse_u8("definitely not a number").expect_err("Parsing should fail");
+    match fail {
+        Decay::Further { note, place, error } => {
+            assert_eq!(note, cadom::Note::NONE);
+            let (line, column) = position_of("let value = text.parse::<u8>()?;", "?");
+            assert_eq!(places(&place), vec![(line, column, Some("parse_u8"))]);
+            assert!(matches!(
+                *error,
+                Decay::External {
+                    error: FailKind::ParseInt(_)
+                }
+            ));
+            let first = place.iter().next().expect("Place should be present");
+            assert_eq!(first.file, "macros/tests/instrument.rs");
+            assert_eq!(first.module(), cadom::place!().module());
+        }
+        _ => panic!("Fail variant should be Decay::Further"),
+    }
+    assert_eq!(parse_u8("42"), Ok(42));
+}
+
+#[test]
+fn question_mark_noted() {
+    let fail = parse_u8_twice("x").expect_err("Parsing should fail");
+    match fail {
+        Decay::Further { note, place, error } => {
+            assert_eq!(note, "Can't parse 'x' twice");
+            let (line, column) = position_of("let value = parse_u8(text)?;", "?");
+            assert_eq!(places(&place), vec![(line, column, Some("parse_u8_twice"))]);
+            assert!(matches!(*error, Decay::Further { .. }));
+        }
+        _ => panic!("Fail variant should be Decay::Further"),
+    }
+    assert_eq!(parse_u8_twice("42"), Ok(42));
+}
+
+#[test]
+fn return_err() {
+    let fail = parse_u8_twice("200").expect_err("Value should be too big");
+    match fail {
+        Decay::Further { note, place, error } => {
+            assert_eq!(note, "Can't parse '200' twice");
+            let (line, column) = position_of("return Err(format!(", "return");
+            assert_eq!(places(&place), vec![(line, column, Some("parse_u8_twice"))]);
+            assert_eq!(
+                *error,
+                Decay::External {
+                    error: FailKind::Custom("Value 200 is too big".to_owned())
+                }
+            );
+        }
+        _ => panic!("Fail variant should be Decay::Further"),
+    }
+}
+
+#[test]
+fn tail_err() {
+    let fail = check_even(3).expect_err("Value should be odd");
+    match fail {
+        Decay::Further { note, place, error } => {
+            assert_eq!(note, "Can't check 3");
+            let (line, column) = position_of("            Err(error)", "Err");
+            assert_eq!(places(&place), vec![(line, column, Some("check_even"))]);
+            assert_eq!(
+                *error,
+                Decay::from(FailKind::Custom("Value 3 is odd".to_owned()))
+            );
+        }
+        _ => panic!("Fail variant should be Decay::Further"),
+    }
+    assert_eq!(check_even(4), Ok(4));
+}
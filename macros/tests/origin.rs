@@ -0,0 +1,77 @@
+use cadom::{Decay, Origin};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(cadom::Origin, thiserror::Error, Clone, Debug, PartialEq, Eq, Serialize, JsonSchema)]
+#[origin(alias = Fail, serde, schema)]
+enum FailKind {
+    #[error("Can't parse integer: {0}")]
+    #[origin(code = "E_PARSE")]
+    ParseInt(String),
+    #[error("{0}")]
+    #[origin(text)]
+    Custom(String),
+    #[error("Timed out after {seconds} s")]
+    TimedOut { seconds: u32 },
+    #[error("Something went wrong with HTTP")]
+    HTTPFailure,
+}
+
+#[derive(cadom::Origin, thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[origin(alias = WrapperFail)]
+enum WrapperKind<E: std::error::Error + 'static> {
+    #[error(transparent)]
+    #[origin(from)]
+    Inner(E),
+}
+
+#[test]
+fn codes() {
+    assert_eq!(FailKind::ParseInt("x".into()).code(), "E_PARSE");
+    assert_eq!(FailKind::Custom("x".into()).code(), "custom");
+    assert_eq!(FailKind::TimedOut { seconds: 1 }.code(), "timed_out");
+    assert_eq!(FailKind::HTTPFailure.code(), "http_failure");
+}
+
+#[test]
+fn text_conversions() {
+    assert_eq!(
+        FailKind::from("Some text"),
+        FailKind::Custom("Some text".into())
+    );
+    assert_eq!(
+        FailKind::from("Some text".to_owned()),
+        FailKind::Custom("Some text".into())
+    );
+}
+
+#[test]
+fn alias_and_decay_code() {
+    let fail: Fail = cadom::rot!("Some note")("Some text");
+    assert_eq!(fail.code(), Some("custom"));
+    assert!(matches!(fail, Decay::Further { .. }));
+    let fail: Fail = cadom::decay!("Internal one");
+    assert_eq!(fail.code(), None);
+}
+
+#[test]
+fn generic_origin() {
+    let parse_error = "x".parse::<u8>().expect_err("Parsing should fail");
+    let fail: WrapperFail<std::num::ParseIntError> = cadom::rot!()(parse_error.clone());
+    assert_eq!(fail.code(), Some("inner"));
+    assert_eq!(
+        fail.root(),
+        cadom::DecayRoot::External {
+            error: &WrapperKind::Inner(parse_error)
+        }
+    );
+}
+
+#[test]
+fn serializable_alias() {
+    let fail: Fail = cadom::rot!("Some note")(FailKind::HTTPFailure);
+    assert_eq!(
+        serde_json::to_string(&fail).expect("Serialization should complete successfully"),
+        r#"["Some note","HTTPFailure"]"#
+    );
+}
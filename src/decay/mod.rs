@@ -2,11 +2,12 @@ use cubob::{Alternate, StructShow};
 use std::{
     error::Error as StdError,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
-    iter::FusedIterator,
+    iter::{FusedIterator, Rev},
     ops::Deref,
+    vec::IntoIter,
 };
 
-use crate::{CodePlace, CodePlaceChain, Note};
+use crate::{CodePlace, CodePlaceChain, Note, ThreadMark};
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum Decay<O: StdError> {
@@ -38,9 +39,12 @@ impl<O: StdError> Decay<O> {
         }
     }
 
+    /// Adds the place and the note as a new level. The empty note adds the place to the current
+    /// level instead (see [Decay::further_unnoted]), unless the note is a deferred one
+    /// (see [Note::deferred]) which is not rendered yet: it always makes a new level.
     pub fn further<N: Into<Note>>(mut self, new_place: CodePlace, note: N) -> Self {
         let note = note.into();
-        match (note.is_none(), &mut self) {
+        match (note.is_unset(), &mut self) {
             (true, Decay::Internal { place, .. } | Decay::Further { place, .. }) => {
                 place.prepend_mut(new_place);
                 self
@@ -74,12 +78,31 @@ impl<O: StdError> Decay<O> {
         |error: E| error.into_decay().further(new_place, note)
     }
 
+    /// Works like [Decay::morph], but the note is produced only when the returned closure is called,
+    /// so nothing is formatted or allocated until an error actually happens.
+    pub fn morph_with<N: Into<Note>, F: FnOnce() -> N, E: IntoDecay<O, L>, const L: usize>(
+        new_place: CodePlace,
+        note: F,
+    ) -> impl FnOnce(E) -> Self {
+        |error: E| error.into_decay().further(new_place, note())
+    }
+
     pub fn morph_unnoted<E: IntoDecay<O, L>, const L: usize>(
         new_place: CodePlace,
     ) -> impl FnOnce(E) -> Self {
         |error: E| error.into_decay().further_unnoted(new_place)
     }
 
+    /// Iterates over levels from the outermost one to the root one.
+    pub fn iter(&self) -> DecayIter<'_, O> {
+        self.into_iter()
+    }
+
+    /// Iterates over levels from the root one to the outermost one.
+    pub fn iter_rev(&self) -> Rev<DecayIter<'_, O>> {
+        self.iter().rev()
+    }
+
     pub fn root(&self) -> DecayRoot<'_, O> {
         let mut current = self;
         loop {
@@ -93,8 +116,41 @@ impl<O: StdError> Decay<O> {
             }
         }
     }
+
+    /// Returns the identity of the thread (and task) where the internal root level was created.
+    /// The external root keeps nothing but the origin error, so for it the mark is taken
+    /// where the first further level was added, which is not necessarily where the error happened:
+    /// e.g. the origin error sent from a worker thread and wrapped by its receiver gets the mark
+    /// of the receiver (see [DecayRoot::External]). The mark is empty without `thread-info` feature
+    /// or if the external root was never wrapped.
+    pub fn thread(&self) -> &ThreadMark {
+        static UNKNOWN: ThreadMark = ThreadMark::NONE;
+        self.iter()
+            .filter_map(|level| match level {
+                Decay::Internal { place, .. } | Decay::Further { place, .. } => {
+                    Some(place.thread())
+                }
+                Decay::External { .. } => None,
+            })
+            .last()
+            .unwrap_or(&UNKNOWN)
+    }
 }
 
+/// Origin which is fine to be carried across threads and tasks. Implemented for every suitable
+/// error type; used to make [SyncDecay] check its parameter.
+pub trait SyncOrigin {
+    type Origin: StdError + Send + Sync + 'static;
+}
+
+impl<O: StdError + Send + Sync + 'static> SyncOrigin for O {
+    type Origin = O;
+}
+
+/// [Decay] which is guaranteed to be `Send + Sync + 'static`: naming it with any origin
+/// lacking those bounds fails to compile. [Decay] itself is `Send` and `Sync` as long as its origin is.
+pub type SyncDecay<O> = Decay<<O as SyncOrigin>::Origin>;
+
 impl<O: StdError> From<O> for Decay<O> {
     fn from(error: O) -> Self {
         Decay::External { error }
@@ -145,6 +201,10 @@ impl<O: StdError> Debug for Decay<O> {
                     .field_opt(&"note", &note.text());
             }
         });
+        let thread = self.thread();
+        if !thread.is_empty() {
+            output.field(&"thread", thread);
+        }
         output.finish()
     }
 }
@@ -154,28 +214,64 @@ impl<'a, O: StdError> IntoIterator for &'a Decay<O> {
     type IntoIter = DecayIter<'a, O>;
 
     fn into_iter(self) -> Self::IntoIter {
-        DecayIter(Some(self))
+        DecayIter::new(self)
     }
 }
 
-pub struct DecayIter<'a, O: StdError>(Option<&'a Decay<O>>);
+/// Iterator over levels of [Decay], going from the outermost level to the innermost one.
+/// Levels are linked in one direction only, so the first step from the back
+/// collects all the remaining levels at once.
+pub struct DecayIter<'a, O: StdError> {
+    front: Option<&'a Decay<O>>,
+    collected: Option<IntoIter<&'a Decay<O>>>,
+}
+
+impl<'a, O: StdError> DecayIter<'a, O> {
+    fn new(decay: &'a Decay<O>) -> Self {
+        Self {
+            front: Some(decay),
+            collected: None,
+        }
+    }
+}
 
 impl<'a, O: StdError> Iterator for DecayIter<'a, O> {
     type Item = &'a Decay<O>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.0 {
+        if let Some(collected) = &mut self.collected {
+            return collected.next();
+        }
+        match self.front {
             Some(current @ Decay::Further { error, .. }) => {
-                self.0 = Some(error);
+                self.front = Some(error);
                 Some(current)
             }
             Some(current) => {
-                self.0 = None;
+                self.front = None;
                 Some(current)
             }
             None => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (&self.collected, self.front) {
+            (Some(collected), _) => collected.size_hint(),
+            (None, Some(_)) => (1, None),
+            (None, None) => (0, Some(0)),
+        }
+    }
+}
+
+impl<O: StdError> DoubleEndedIterator for DecayIter<'_, O> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.collected.is_none() {
+            let remaining = self.by_ref().collect::<Vec<_>>();
+            self.collected = Some(remaining.into_iter());
+        }
+        self.collected.as_mut()?.next_back()
+    }
 }
 
 impl<O: StdError> FusedIterator for DecayIter<'_, O> {}
@@ -190,15 +286,19 @@ impl<O: StdError + 'static> StdError for Decay<O> {
     }
 }
 
+/// Innermost level of [Decay], as returned by [Decay::root].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DecayRoot<'a, O: StdError> {
+    /// The place chain also carries the [ThreadMark] of the thread where the root was created.
     Internal {
         note: &'a Note,
         place: &'a CodePlaceChain,
     },
-    External {
-        error: &'a O,
-    },
+    /// The origin error carries no [ThreadMark]: [Decay::thread] reports the thread
+    /// which has added the first further level instead, since `From<O>` has nowhere to keep the mark.
+    /// Wrap the error where it happens (e.g. with `rot!()`) before sending it to another thread
+    /// to keep the right mark.
+    External { error: &'a O },
 }
 
 #[macro_export]
@@ -212,10 +312,50 @@ macro_rules! decay {
     };
 
     ($format:expr, $($rest:tt)*) => {
-        $crate::Decay::new($crate::place!(), format!($format, $($rest)*))
+        $crate::Decay::new($crate::place!(), $crate::__templated_note!($crate, $format, $($rest)*))
+    };
+}
+
+/// Returns early with the internal [Decay] error, created like [decay!] does.
+#[macro_export]
+macro_rules! bail {
+    () => {
+        return ::std::result::Result::Err($crate::decay!())
+    };
+
+    ($text:expr) => {
+        return ::std::result::Result::Err($crate::decay!($text))
+    };
+
+    ($format:expr, $($rest:tt)*) => {
+        return ::std::result::Result::Err($crate::decay!($format, $($rest)*))
+    };
+}
+
+/// Returns early with the internal [Decay] error if the condition is false.
+/// Without the note given the condition text itself is used as the note.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::bail!(concat!("Condition failed: `", stringify!($cond), "`"))
+        }
+    };
+
+    ($cond:expr, $($rest:tt)+) => {
+        if !$cond {
+            $crate::bail!($($rest)+)
+        }
     };
 }
 
+/// Returns the closure which turns an error into [Decay] with the place of the macro call
+/// (and the note, if given) added, suitable for [Result::map_err].
+///
+/// The note given as format string and arguments is made only when the closure is called,
+/// i.e. when the error actually happens: the arguments are evaluated inside the closure
+/// and are taken by reference, so neither their evaluation nor formatting costs anything
+/// on the success path, and they are not moved into the closure.
 #[macro_export]
 macro_rules! rot {
     () => {
@@ -227,9 +367,10 @@ macro_rules! rot {
     };
 
     ($format:expr, $($rest:tt)*) => {
-        $crate::Decay::morph($crate::place!(), format!($format, $($rest)*))
+        $crate::Decay::morph_with($crate::place!(), || $crate::__templated_note!($crate, $format, $($rest)*))
     };
 }
 
+mod report;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,29 @@
+//! Summaries of [Decay] shared by the integrations reporting it to monitoring systems.
+
+use std::error::Error as StdError;
+
+use super::Decay;
+
+impl<O: StdError> Decay<O> {
+    /// Returns the origin type name for the external decay and `cadom::Decay` otherwise.
+    #[cfg(feature = "opentelemetry")]
+    pub(crate) fn root_type_name(&self) -> &'static str {
+        match self.root() {
+            super::DecayRoot::External { .. } => std::any::type_name::<O>(),
+            super::DecayRoot::Internal { .. } => "cadom::Decay",
+        }
+    }
+
+    /// Returns the outermost note, or the origin message if there are no notes.
+    #[cfg(any(feature = "log", feature = "opentelemetry"))]
+    pub(crate) fn headline(&self) -> String {
+        self.iter()
+            .find_map(|level| match level {
+                Decay::External { error } => Some(error.to_string()),
+                Decay::Internal { note, .. } | Decay::Further { note, .. } => {
+                    note.text().map(str::to_owned)
+                }
+            })
+            .unwrap_or_default()
+    }
+}
@@ -231,3 +231,150 @@ fn decay_root_external() {
         DecayRoot::External { error } => assert_eq!(error, &custom_error),
     }
 }
+
+#[test]
+fn iter_both_directions() {
+    let fail: Fail = rot!("Outer note")(Fail::new(place!(), "Root note"));
+    let fail: Fail = rot!()(fail);
+    let fail: Fail = rot!("Outermost note")(fail);
+
+    let notes = |levels: &mut dyn Iterator<Item = &Fail>| -> Vec<Option<String>> {
+        levels
+            .map(|level| match level {
+                Decay::Internal { note, .. } | Decay::Further { note, .. } => {
+                    note.text().map(ToOwned::to_owned)
+                }
+                Decay::External { .. } => None,
+            })
+            .collect()
+    };
+
+    assert_eq!(fail.iter().count(), 3);
+    assert_eq!(
+        notes(&mut fail.iter()),
+        vec![
+            Some("Outermost note".to_owned()),
+            Some("Outer note".to_owned()),
+            Some("Root note".to_owned())
+        ]
+    );
+    assert_eq!(
+        notes(&mut fail.iter_rev()),
+        vec![
+            Some("Root note".to_owned()),
+            Some("Outer note".to_owned()),
+            Some("Outermost note".to_owned())
+        ]
+    );
+
+    let mut levels = fail.iter();
+    assert!(matches!(levels.next_back(), Some(Decay::Internal { .. })));
+    assert_eq!(levels.size_hint(), (2, Some(2)));
+    assert!(matches!(levels.next(), Some(Decay::Further { .. })));
+    assert!(matches!(levels.next_back(), Some(Decay::Further { .. })));
+    assert_eq!(levels.size_hint(), (0, Some(0)));
+    assert!(levels.next().is_none());
+    assert!(levels.next_back().is_none());
+}
+
+#[test]
+fn iter_single_level() {
+    let fail = Fail::from(FailKind::Custom("Some error".into()));
+    assert_eq!(fail.iter().count(), 1);
+    assert_eq!(fail.iter_rev().next(), Some(&fail));
+    assert_eq!(fail.iter().next(), Some(&fail));
+}
+
+fn check_bail(value: u8) -> Result<u8, Fail> {
+    match value {
+        0 => bail!(),
+        1 => bail!("Static note"),
+        2 => bail!("Formatted note #{}", value),
+        _ => Ok(value),
+    }
+}
+
+fn check_ensure(value: u8) -> Result<u8, Fail> {
+    ensure!(value > 0);
+    ensure!(value > 1, "Static note");
+    ensure!(value > 2, "Formatted note #{}", value,);
+    Ok(value)
+}
+
+fn assert_internal(fail: Fail, expected_note: Option<&str>, expected_line: u32) {
+    match fail {
+        Decay::Internal { note, place } => {
+            assert_eq!(note.text(), expected_note);
+            let places = place.iter().collect::<Vec<_>>();
+            assert_eq!(places.len(), 1);
+            assert_eq!(places[0].file, "src/decay/tests.rs");
+            assert_eq!(places[0].line, expected_line);
+        }
+        _ => panic!("Fail variant should be Decay::Internal"),
+    }
+}
+
+#[test]
+fn bail_variants() {
+    assert_internal(check_bail(0).unwrap_err(), None, 290);
+    assert_internal(check_bail(1).unwrap_err(), Some("Static note"), 291);
+    assert_internal(check_bail(2).unwrap_err(), Some("Formatted note #2"), 292);
+    assert_eq!(check_bail(3), Ok(3));
+}
+
+#[test]
+fn ensure_variants() {
+    assert_internal(
+        check_ensure(0).unwrap_err(),
+        Some("Condition failed: `value > 0`"),
+        298,
+    );
+    assert_internal(check_ensure(1).unwrap_err(), Some("Static note"), 299);
+    assert_internal(check_ensure(2).unwrap_err(), Some("Formatted note #2"), 300);
+    assert_eq!(check_ensure(3), Ok(3));
+}
+
+mod thread_safety {
+    use std::{cell::Cell, thread};
+
+    use super::*;
+    use crate::{CodePlaceChain, DecayDisplay, Note, SyncDecay};
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    fn assert_send<T: Send + 'static>() {}
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("Unsynchronized error {}", .0.get())]
+    struct UnsyncErr(Cell<u8>);
+
+    #[test]
+    fn static_assertions() {
+        assert_send_sync::<Note>();
+        assert_send_sync::<CodePlace>();
+        assert_send_sync::<CodePlaceChain>();
+        assert_send_sync::<Fail>();
+        assert_send_sync::<SyncDecay<FailKind>>();
+        assert_send_sync::<SyncDecay<std::io::Error>>();
+        assert_send_sync::<DecayDisplay<'static, FailKind>>();
+        assert_send::<Decay<UnsyncErr>>();
+    }
+
+    #[test]
+    fn across_threads() {
+        let fail: SyncDecay<FailKind> = decay!("Deferred note {}", 12);
+        let fail = thread::spawn(move || fail.further(place!(), Note::deferred("Outer note")))
+            .join()
+            .expect("Thread should not panic");
+        assert_eq!(fail.iter().count(), 2);
+
+        let fail = Decay::from(UnsyncErr(Cell::new(7))).further(place!(), "Outer note");
+        let rendered = thread::spawn(move || fail.to_string())
+            .join()
+            .expect("Thread should not panic");
+        assert_eq!(
+            rendered,
+            "{note: Outer note, error: Unsynchronized error 7}"
+        );
+    }
+}
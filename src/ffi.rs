@@ -0,0 +1,254 @@
+//! C ABI for creating and inspecting [Decay] values from foreign code.
+//! The header is committed as `include/cadom.h`. The test harness in `tests/ffi` checks it
+//! against this module and regenerates it with `CADOM_UPDATE_HEADER=1 cargo test -p cadom-ffi-harness`.
+//! Panics never unwind into foreign code: functions catch them and return null (or zero) instead.
+
+use std::{
+    error::Error as StdError,
+    ffi::{c_char, c_void, CStr, CString},
+    fmt::{Display, Formatter, Result as FmtResult},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use crate::{
+    place::{static_file, UNKNOWN_FILE},
+    CodePlace, Decay, Note,
+};
+
+/// Error passed from foreign code as the root of [Decay].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignError(pub String);
+
+impl Display for ForeignError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.0)
+    }
+}
+
+impl StdError for ForeignError {}
+
+/// Opaque handle of [Decay] owned by foreign code.
+pub struct CadomDecay(Decay<ForeignError>);
+
+/// Borrowed UTF-8 text, which is not NUL-terminated. Null pointer stands for the absent text.
+#[repr(C)]
+pub struct CadomStr {
+    pub ptr: *const c_char,
+    pub len: usize,
+}
+
+impl CadomStr {
+    const NONE: Self = Self {
+        ptr: ptr::null(),
+        len: 0,
+    };
+
+    fn new(text: &str) -> Self {
+        Self {
+            ptr: text.as_ptr().cast(),
+            len: text.len(),
+        }
+    }
+}
+
+/// Called for every level, from the outermost one to the root one, with the note of the level
+/// (or the message of the foreign error for the external level). May be null.
+pub type CadomNoteVisitor =
+    Option<extern "C" fn(context: *mut c_void, level: usize, note: CadomStr)>;
+
+/// Called for every place of every level, in the same order as places are rendered. May be null.
+pub type CadomPlaceVisitor = Option<
+    extern "C" fn(context: *mut c_void, level: usize, file: CadomStr, line: u32, column: u32),
+>;
+
+/// # Safety
+/// The pointer should be either null or point to the valid NUL-terminated string.
+unsafe fn text_of(text: *const c_char) -> Option<String> {
+    match text.is_null() {
+        true => None,
+        false => Some(CStr::from_ptr(text).to_string_lossy().into_owned()),
+    }
+}
+
+/// # Safety
+/// The pointer should point to the valid NUL-terminated string (null is treated as the unknown file).
+unsafe fn place_of(file: *const c_char, line: u32, column: u32) -> CodePlace {
+    let file = match text_of(file) {
+        Some(file) => static_file(&file),
+        None => UNKNOWN_FILE,
+    };
+    CodePlace::new(file, line, column)
+}
+
+fn into_handle(decay: Decay<ForeignError>) -> *mut CadomDecay {
+    Box::into_raw(Box::new(CadomDecay(decay)))
+}
+
+/// Runs the body of the exported function, returning the fallback if the body panics,
+/// since unwinding out of `extern "C"` function aborts the process.
+fn guarded<T, F: FnOnce() -> T>(fallback: T, body: F) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Creates the internal decay. The note may be null.
+///
+/// # Safety
+/// Non-null string pointers should point to valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_new(
+    file: *const c_char,
+    line: u32,
+    column: u32,
+    note: *const c_char,
+) -> *mut CadomDecay {
+    guarded(ptr::null_mut(), || {
+        into_handle(Decay::new(
+            place_of(file, line, column),
+            Note::from(text_of(note)),
+        ))
+    })
+}
+
+/// Creates the external decay from the foreign error message.
+///
+/// # Safety
+/// Non-null message pointer should point to the valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_from_error(message: *const c_char) -> *mut CadomDecay {
+    guarded(ptr::null_mut(), || {
+        into_handle(Decay::from(ForeignError(
+            text_of(message).unwrap_or_default(),
+        )))
+    })
+}
+
+/// Appends the further level (just like `rot!` does) and returns the resulting decay.
+/// The given handle is consumed and should not be used anymore. The note may be null.
+///
+/// # Safety
+/// The handle should be the valid one returned by this library; non-null string pointers
+/// should point to valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_further(
+    decay: *mut CadomDecay,
+    file: *const c_char,
+    line: u32,
+    column: u32,
+    note: *const c_char,
+) -> *mut CadomDecay {
+    if decay.is_null() {
+        return ptr::null_mut();
+    }
+    guarded(ptr::null_mut(), || {
+        let CadomDecay(decay) = *Box::from_raw(decay);
+        into_handle(decay.further(place_of(file, line, column), Note::from(text_of(note))))
+    })
+}
+
+/// Returns the amount of levels (zero for the null handle).
+///
+/// # Safety
+/// The handle should be either null or the valid one returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_depth(decay: *const CadomDecay) -> usize {
+    guarded(0, || match decay.as_ref() {
+        Some(CadomDecay(decay)) => decay.iter().count(),
+        None => 0,
+    })
+}
+
+/// Calls the visitor for the note of every level. Texts are valid during the call only.
+/// Nothing is done if either the handle or the visitor is null.
+///
+/// # Safety
+/// The handle should be either null or the valid one returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_visit_notes(
+    decay: *const CadomDecay,
+    visitor: CadomNoteVisitor,
+    context: *mut c_void,
+) {
+    let (CadomDecay(decay), visitor) = match (decay.as_ref(), visitor) {
+        (Some(decay), Some(visitor)) => (decay, visitor),
+        _ => return,
+    };
+    guarded((), || {
+        for (index, level) in decay.iter().enumerate() {
+            match level {
+                Decay::External { error } => visitor(context, index, CadomStr::new(&error.0)),
+                Decay::Internal { note, .. } | Decay::Further { note, .. } => {
+                    let note = note.text().map_or(CadomStr::NONE, CadomStr::new);
+                    visitor(context, index, note)
+                }
+            }
+        }
+    })
+}
+
+/// Calls the visitor for every place of every level. Texts are valid during the call only.
+/// Nothing is done if either the handle or the visitor is null.
+///
+/// # Safety
+/// The handle should be either null or the valid one returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_visit_places(
+    decay: *const CadomDecay,
+    visitor: CadomPlaceVisitor,
+    context: *mut c_void,
+) {
+    let (CadomDecay(decay), visitor) = match (decay.as_ref(), visitor) {
+        (Some(decay), Some(visitor)) => (decay, visitor),
+        _ => return,
+    };
+    guarded((), || {
+        for (index, level) in decay.iter().enumerate() {
+            if let Decay::Internal { place, .. } | Decay::Further { place, .. } = level {
+                for place in place {
+                    let file = CadomStr::new(place.file);
+                    visitor(context, index, file, place.line, place.column);
+                }
+            }
+        }
+    })
+}
+
+/// Renders the decay with the process-wide format (see `DecayFormat::set_global`).
+/// The result should be released with [cadom_string_free]; null is returned for the null handle.
+///
+/// # Safety
+/// The handle should be either null or the valid one returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_render(decay: *const CadomDecay) -> *mut c_char {
+    let CadomDecay(decay) = match decay.as_ref() {
+        Some(decay) => decay,
+        None => return ptr::null_mut(),
+    };
+    guarded(ptr::null_mut(), || {
+        let mut rendered = decay.display_global().to_string();
+        rendered.retain(|c| c != '\0');
+        CString::new(rendered).map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Releases the string returned by [cadom_decay_render]. Null is ignored.
+///
+/// # Safety
+/// The string should be either null or the one returned by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        guarded((), || drop(CString::from_raw(text)))
+    }
+}
+
+/// Releases the decay. Null is ignored.
+///
+/// # Safety
+/// The handle should be either null or the valid one returned by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn cadom_decay_free(decay: *mut CadomDecay) {
+    if !decay.is_null() {
+        guarded((), || drop(Box::from_raw(decay)))
+    }
+}
@@ -0,0 +1,170 @@
+use std::error::Error as StdError;
+
+use crate::{Decay, DecayRoot, Origin};
+
+/// 64-bit FNV-1a hash, chosen since its result doesn't depend on the platform or the Rust version
+/// (unlike the one of [std::collections::hash_map::DefaultHasher]).
+struct Fnv(u64);
+
+impl Fnv {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes the text followed by the terminator, so adjacent texts can't be confused.
+    fn write_str(&mut self, text: &str) {
+        self.write(text.as_bytes());
+        self.write(&[0xff]);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+}
+
+impl<O: StdError> Decay<O> {
+    /// Returns the hash of places of all levels and of format templates of notes (see [Note::template]),
+    /// which stays the same for equivalent failures no matter what the rendered notes (or the origin
+    /// message) say, so it's fine for grouping errors in monitoring systems. The hash is stable across
+    /// platforms and builds as long as the code places and templates are intact. The origin type
+    /// is not hashed, since its name is not stable, so external roots differ by places only
+    /// (see [Decay::coded_fingerprint] for origins with codes).
+    ///
+    /// [Note::template]: crate::Note::template
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint_with_code(None)
+    }
+
+    fn fingerprint_with_code(&self, code: Option<&str>) -> u64 {
+        let mut hash = Fnv(Fnv::OFFSET);
+        match self.root() {
+            DecayRoot::External { .. } => hash.write_str(code.unwrap_or_default()),
+            DecayRoot::Internal { .. } => hash.write_str("cadom::Decay"),
+        }
+        for level in self {
+            match level {
+                Decay::External { .. } => hash.write(&[0]),
+                Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => {
+                    hash.write(&[1]);
+                    for place in place {
+                        hash.write_str(place.file);
+                        hash.write_u32(place.line);
+                        hash.write_u32(place.column);
+                    }
+                    if let Some(template) = note.template() {
+                        hash.write(&[2]);
+                        hash.write_str(template);
+                    }
+                }
+            }
+        }
+        hash.0
+    }
+}
+
+impl<O: Origin> Decay<O> {
+    /// Returns the same hash as [Decay::fingerprint] does, but with the classification code
+    /// of the external root (see [Origin::code]) hashed as well, so roots of different codes
+    /// at the same places are told apart.
+    pub fn coded_fingerprint(&self) -> u64 {
+        self.fingerprint_with_code(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodePlace;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection to {0} reset")]
+    struct TestErr(&'static str);
+
+    impl Origin for TestErr {
+        fn code(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    type Fail = Decay<TestErr>;
+
+    fn request(host: &'static str, attempt: u8) -> Fail {
+        Fail::from(TestErr(host))
+            .further(
+                CodePlace::new("src/net.rs", 12, 5),
+                format!("Can't read the response from {}", host),
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(
+                CodePlace::new("src/main.rs", 7, 1),
+                format!("Attempt {} failed", attempt),
+            )
+    }
+
+    #[test]
+    fn fingerprint() {
+        let fingerprint = request("a.example", 1).fingerprint();
+        assert_eq!(fingerprint, request("b.example", 2).fingerprint());
+
+        let moved = Fail::from(TestErr("a.example"))
+            .further(
+                CodePlace::new("src/net.rs", 13, 5),
+                "Can't read the response",
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(CodePlace::new("src/main.rs", 7, 1), "Attempt failed");
+        assert_ne!(fingerprint, moved.fingerprint());
+
+        // Same places split into levels differently.
+        let regrouped = Fail::from(TestErr("a.example"))
+            .further(
+                CodePlace::new("src/net.rs", 12, 5),
+                "Can't read the response",
+            )
+            .further(CodePlace::new("src/client.rs", 40, 9), "Client failed")
+            .further(CodePlace::new("src/main.rs", 7, 1), "Attempt failed");
+        assert_ne!(fingerprint, regrouped.fingerprint());
+
+        let internal = Fail::new(CodePlace::new("src/net.rs", 12, 5), "Can't read");
+        let other_internal = Fail::new(CodePlace::new("src/net.rs", 12, 5), "Can't write");
+        assert_eq!(internal.fingerprint(), other_internal.fingerprint());
+        assert_eq!(
+            internal.fingerprint(),
+            Decay::<std::fmt::Error>::new(CodePlace::new("src/net.rs", 12, 5), "Can't read")
+                .fingerprint(),
+            "Internal decays don't depend on the origin type"
+        );
+        assert_eq!(internal.fingerprint(), 7880990701939439320);
+    }
+
+    #[test]
+    fn templated_fingerprint() {
+        let place = CodePlace::new("src/users.rs", 21, 9);
+        let missing = |id: u32| Fail::new(place.clone(), note!("User {} not found", id));
+        assert_eq!(missing(1).fingerprint(), missing(2).fingerprint());
+        assert_ne!(
+            missing(1).fingerprint(),
+            Fail::new(place.clone(), note!("User {} is banned", 1)).fingerprint()
+        );
+        assert_ne!(
+            missing(1).fingerprint(),
+            Fail::new(place, "User 1 not found").fingerprint()
+        );
+    }
+
+    #[test]
+    fn coded_fingerprint() {
+        let fingerprint = request("a.example", 1).coded_fingerprint();
+        assert_eq!(fingerprint, request("a.example", 2).coded_fingerprint());
+        assert_ne!(fingerprint, request("b.example", 1).coded_fingerprint());
+        assert_ne!(fingerprint, request("a.example", 1).fingerprint());
+
+        let internal = Fail::new(CodePlace::new("src/net.rs", 12, 5), "Can't read");
+        assert_eq!(internal.coded_fingerprint(), internal.fingerprint());
+    }
+}
@@ -0,0 +1,586 @@
+use cubob::{Alternate, ListShow, StructShow};
+use std::{
+    borrow::Cow,
+    error::Error as StdError,
+    fmt::{Debug, Display, Formatter, Result as FmtResult, Write},
+    iter::Rev,
+    path::{Path, MAIN_SEPARATOR},
+    sync::RwLock,
+};
+
+use crate::{
+    note::{opt_text, StaticCowStr},
+    CodePlace, CodePlaceChain, Decay, Note, ThreadMark,
+};
+
+/// Which parts of every [CodePlace] position should be replaced with the mask symbol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaceMask {
+    /// Position is shown as is.
+    Nothing,
+    /// Only the column number is masked.
+    Column,
+    /// Both line and column numbers are masked.
+    Position,
+}
+
+/// How file paths of [CodePlace] are rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathStyle {
+    /// The path is shown as captured by `file!()` (with root stripping and normalization applied).
+    Relative,
+    /// The absolute path is shown when it can be resolved (see [CodePlace::absolute_file]).
+    Absolute,
+    /// The module path (like `crate::module`) is shown instead of the file path when known.
+    Module,
+    /// The relative path is shown as a terminal hyperlink (OSC 8) to the absolute one when it can be resolved.
+    Hyperlink,
+}
+
+/// Order in which levels of [Decay] (and places inside every level) are rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecayOrder {
+    /// The most recently added level goes first, the root one goes last.
+    OutermostFirst,
+    /// The root level goes first, the most recently added one goes last.
+    InnermostFirst,
+}
+
+static GLOBAL_FORMAT: RwLock<DecayFormat> = RwLock::new(DecayFormat::new());
+
+/// Set of options for rendering [Decay] through [Decay::display_with].
+/// Default options produce the same output as [Debug] implementation of [Decay] does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecayFormat {
+    root: Option<StaticCowStr>,
+    normalize_paths: bool,
+    mask: PlaceMask,
+    notes_only: bool,
+    paths: PathStyle,
+    places: bool,
+    order: DecayOrder,
+    separator: Option<StaticCowStr>,
+    max_depth: Option<usize>,
+    max_note_len: Option<usize>,
+    collapse_places: bool,
+    threads: bool,
+}
+
+impl DecayFormat {
+    pub const MASK_SYMBOL: char = '*';
+    pub const ELLIPSIS: char = '…';
+
+    pub const fn new() -> Self {
+        Self {
+            root: None,
+            normalize_paths: false,
+            mask: PlaceMask::Nothing,
+            notes_only: false,
+            paths: PathStyle::Relative,
+            places: true,
+            order: DecayOrder::OutermostFirst,
+            separator: None,
+            max_depth: None,
+            max_note_len: None,
+            collapse_places: false,
+            threads: true,
+        }
+    }
+
+    /// Options suitable for snapshot testing: paths are normalized, positions are masked
+    /// and thread marks are hidden.
+    pub fn snapshot() -> Self {
+        Self {
+            normalize_paths: true,
+            mask: PlaceMask::Position,
+            threads: false,
+            ..Self::new()
+        }
+    }
+
+    /// Sets the prefix to be stripped from every [CodePlace] file path (if present there).
+    pub fn with_root<R: Into<StaticCowStr>>(mut self, root: R) -> Self {
+        self.root = opt_text(root.into());
+        self
+    }
+
+    /// Turns on or off replacing backslashes with forward slashes in paths.
+    pub fn with_normalized_paths(mut self, normalize_paths: bool) -> Self {
+        self.normalize_paths = normalize_paths;
+        self
+    }
+
+    pub fn with_mask(mut self, mask: PlaceMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Turns on or off the mode when only notes are shown, without places and external errors.
+    pub fn with_notes_only(mut self, notes_only: bool) -> Self {
+        self.notes_only = notes_only;
+        self
+    }
+
+    pub fn with_paths(mut self, paths: PathStyle) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Turns on or off showing places of every level.
+    pub fn with_places(mut self, places: bool) -> Self {
+        self.places = places;
+        self
+    }
+
+    pub fn with_order(mut self, order: DecayOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the separator to put between levels. When set, every level is rendered
+    /// as a separate struct, otherwise all levels share the only one struct.
+    pub fn with_separator(mut self, separator: Option<&'static str>) -> Self {
+        self.separator = separator.map(Into::into);
+        self
+    }
+
+    /// Limits the amount of levels shown, the rest are reported as omitted.
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limits the length of every note (in chars), longer notes are truncated with [Self::ELLIPSIS].
+    pub fn with_max_note_len(mut self, max_note_len: Option<usize>) -> Self {
+        self.max_note_len = max_note_len;
+        self
+    }
+
+    /// Turns on or off showing consecutive equal places of one level as a single one with repeats count.
+    pub fn with_collapsed_places(mut self, collapse_places: bool) -> Self {
+        self.collapse_places = collapse_places;
+        self
+    }
+
+    /// Turns on or off showing the [ThreadMark] of the root level (when it is captured).
+    pub fn with_threads(mut self, threads: bool) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Replaces the process-wide format used by [Decay::display_global].
+    pub fn set_global(format: DecayFormat) {
+        match GLOBAL_FORMAT.write() {
+            Ok(mut global) => *global = format,
+            Err(poisoned) => *poisoned.into_inner() = format,
+        }
+    }
+
+    /// Returns a copy of the process-wide format, which is [DecayFormat::new] unless set otherwise.
+    pub fn global() -> DecayFormat {
+        match GLOBAL_FORMAT.read() {
+            Ok(global) => global.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    pub fn root(&self) -> Option<&str> {
+        self.root.as_deref()
+    }
+
+    pub fn normalize_paths(&self) -> bool {
+        self.normalize_paths
+    }
+
+    pub fn mask(&self) -> PlaceMask {
+        self.mask
+    }
+
+    pub fn notes_only(&self) -> bool {
+        self.notes_only
+    }
+
+    pub fn paths(&self) -> PathStyle {
+        self.paths
+    }
+
+    pub fn places(&self) -> bool {
+        self.places
+    }
+
+    pub fn order(&self) -> DecayOrder {
+        self.order
+    }
+
+    pub fn separator(&self) -> Option<&str> {
+        self.separator.as_deref()
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    pub fn max_note_len(&self) -> Option<usize> {
+        self.max_note_len
+    }
+
+    pub fn collapse_places(&self) -> bool {
+        self.collapse_places
+    }
+
+    pub fn threads(&self) -> bool {
+        self.threads
+    }
+
+    fn write_path(&self, f: &mut Formatter<'_>, path: &str) -> FmtResult {
+        let path = match self
+            .root
+            .as_deref()
+            .and_then(|root| self.strip_root(path, root))
+        {
+            Some(relative) => relative,
+            None => path,
+        };
+        self.write_normalized(f, path)
+    }
+
+    fn write_normalized(&self, f: &mut Formatter<'_>, path: &str) -> FmtResult {
+        match self.normalize_paths {
+            false => f.write_str(path),
+            true => path
+                .chars()
+                .try_for_each(|c| f.write_char(if c == '\\' { '/' } else { c })),
+        }
+    }
+
+    fn strip_root<'p>(&self, path: &'p str, root: &str) -> Option<&'p str> {
+        let mut path_chars = path.char_indices();
+        for root_char in root.chars() {
+            match path_chars.next() {
+                Some((_, path_char)) if self.same_path_chars(path_char, root_char) => {}
+                _ => return None,
+            }
+        }
+        let rest = path_chars.as_str();
+        match root.ends_with(['/', '\\']) {
+            true => Some(rest),
+            false => rest.strip_prefix(['/', '\\']),
+        }
+    }
+
+    fn same_path_chars(&self, c1: char, c2: char) -> bool {
+        c1 == c2 || (self.normalize_paths && matches!((c1, c2), ('/', '\\') | ('\\', '/')))
+    }
+
+    fn write_place(&self, f: &mut Formatter<'_>, place: &CodePlace) -> FmtResult {
+        match (self.paths, place.module(), place.function()) {
+            (PathStyle::Module, Some(module), function) => {
+                f.write_str(module)?;
+                if let Some(function) = function {
+                    write!(f, "::{}", function)?;
+                }
+                self.write_position(f, place)
+            }
+            (_, module, Some(function)) => {
+                if let Some(module) = module {
+                    write!(f, "{}::", module)?;
+                }
+                write!(f, "{} (", function)?;
+                self.write_location(f, place)?;
+                f.write_char(')')
+            }
+            _ => self.write_location(f, place),
+        }
+    }
+
+    fn write_location(&self, f: &mut Formatter<'_>, place: &CodePlace) -> FmtResult {
+        match self.paths {
+            PathStyle::Relative | PathStyle::Module => {
+                self.write_path(f, place.file)?;
+                self.write_position(f, place)
+            }
+            PathStyle::Absolute => {
+                match place.absolute_file() {
+                    Some(absolute) => self.write_normalized(f, &absolute.to_string_lossy())?,
+                    None => self.write_path(f, place.file)?,
+                }
+                self.write_position(f, place)
+            }
+            PathStyle::Hyperlink => match place.absolute_file() {
+                Some(absolute) => {
+                    f.write_str("\x1b]8;;")?;
+                    Self::write_file_uri(f, &absolute)?;
+                    f.write_str("\x1b\\")?;
+                    self.write_path(f, place.file)?;
+                    self.write_position(f, place)?;
+                    f.write_str("\x1b]8;;\x1b\\")
+                }
+                None => {
+                    self.write_path(f, place.file)?;
+                    self.write_position(f, place)
+                }
+            },
+        }
+    }
+
+    /// Writes the `file:` URI of the absolute path: separators become slashes, Windows drive paths
+    /// get the leading slash (`file:///C:/...`) and bytes not allowed in URI paths are percent-encoded.
+    fn write_file_uri(f: &mut Formatter<'_>, absolute: &Path) -> FmtResult {
+        let path = absolute.to_string_lossy();
+        f.write_str("file://")?;
+        if !path.starts_with(MAIN_SEPARATOR) {
+            f.write_char('/')?;
+        }
+        for byte in path.bytes() {
+            match byte {
+                _ if byte == MAIN_SEPARATOR as u8 => f.write_char('/')?,
+                // Unreserved characters and the ones allowed in path segments.
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                    f.write_char(byte as char)?
+                }
+                b':' | b'@' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b','
+                | b';' | b'=' => f.write_char(byte as char)?,
+                _ => write!(f, "%{:02X}", byte)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_position(&self, f: &mut Formatter<'_>, place: &CodePlace) -> FmtResult {
+        match self.mask {
+            PlaceMask::Nothing => write!(f, ":{}:{}", place.line, place.column),
+            PlaceMask::Column => write!(f, ":{}:{}", place.line, Self::MASK_SYMBOL),
+            PlaceMask::Position => write!(f, ":{}:{}", Self::MASK_SYMBOL, Self::MASK_SYMBOL),
+        }
+    }
+
+    fn write_note(&self, f: &mut Formatter<'_>, text: &str) -> FmtResult {
+        match self
+            .max_note_len
+            .and_then(|max_len| text.char_indices().nth(max_len))
+        {
+            None => f.write_str(text),
+            Some((cut, _)) => {
+                f.write_str(&text[..cut])?;
+                f.write_char(Self::ELLIPSIS)
+            }
+        }
+    }
+
+    fn ordered<I: DoubleEndedIterator>(&self, items: I) -> Ordered<I> {
+        match self.order {
+            DecayOrder::OutermostFirst => Ordered::Forward(items),
+            DecayOrder::InnermostFirst => Ordered::Backward(items.rev()),
+        }
+    }
+
+    fn level_fields<O: StdError>(
+        &self,
+        output: &mut StructShow<'_, '_>,
+        level: &Decay<O>,
+        thread: &ThreadMark,
+    ) {
+        match level {
+            Decay::External { error } => {
+                if !self.notes_only {
+                    output.field(&"error", error);
+                }
+                self.thread_field(output, thread);
+            }
+            Decay::Internal { note, place } => {
+                self.chain_fields(output, note, place);
+                self.thread_field(output, thread);
+            }
+            Decay::Further { note, place, .. } => self.chain_fields(output, note, place),
+        }
+    }
+
+    fn chain_fields(&self, output: &mut StructShow<'_, '_>, note: &Note, place: &CodePlaceChain) {
+        if self.places && !self.notes_only {
+            output.field_override(
+                &"place",
+                &FormattedChain {
+                    chain: place,
+                    format: self,
+                },
+                Alternate::OneLine,
+            );
+        }
+        if let Some(text) = note.text() {
+            output.field(&"note", &FormattedNote { text, format: self });
+        }
+    }
+
+    fn thread_field(&self, output: &mut StructShow<'_, '_>, thread: &ThreadMark) {
+        if self.threads && !self.notes_only && !thread.is_empty() {
+            output.field(&"thread", thread);
+        }
+    }
+}
+
+impl Default for DecayFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> From<&'a DecayFormat> for Cow<'a, DecayFormat> {
+    fn from(src: &'a DecayFormat) -> Self {
+        Cow::Borrowed(src)
+    }
+}
+
+impl From<DecayFormat> for Cow<'_, DecayFormat> {
+    fn from(src: DecayFormat) -> Self {
+        Cow::Owned(src)
+    }
+}
+
+enum Ordered<I: DoubleEndedIterator> {
+    Forward(I),
+    Backward(Rev<I>),
+}
+
+impl<I: DoubleEndedIterator> Iterator for Ordered<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Ordered::Forward(items) => items.next(),
+            Ordered::Backward(items) => items.next(),
+        }
+    }
+}
+
+struct FormattedPlace<'a> {
+    place: &'a CodePlace,
+    repeats: usize,
+    format: &'a DecayFormat,
+}
+
+impl Display for FormattedPlace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.format.write_place(f, self.place)?;
+        match self.repeats {
+            0 | 1 => Ok(()),
+            repeats => write!(f, " (x{})", repeats),
+        }
+    }
+}
+
+struct FormattedChain<'a> {
+    chain: &'a CodePlaceChain,
+    format: &'a DecayFormat,
+}
+
+impl Display for FormattedChain<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let format = self.format;
+        let mut output = ListShow::new(f, Alternate::Inherit);
+        let mut places = format.ordered(self.chain.iter());
+        let mut current = places.next();
+        while let Some(place) = current {
+            let mut repeats = 1;
+            current = places.next();
+            while format.collapse_places && current == Some(place) {
+                repeats += 1;
+                current = places.next();
+            }
+            output.item(&FormattedPlace {
+                place,
+                repeats,
+                format,
+            });
+        }
+        output.finish()
+    }
+}
+
+struct FormattedNote<'a> {
+    text: &'a str,
+    format: &'a DecayFormat,
+}
+
+impl Display for FormattedNote<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.format.write_note(f, self.text)
+    }
+}
+
+/// Displayable view of [Decay] rendered with some [DecayFormat].
+/// Both [Display] and [Debug] implementations produce the same output.
+pub struct DecayDisplay<'a, O: StdError> {
+    decay: &'a Decay<O>,
+    format: Cow<'a, DecayFormat>,
+}
+
+impl<'a, O: StdError> DecayDisplay<'a, O> {
+    pub fn new<F: Into<Cow<'a, DecayFormat>>>(decay: &'a Decay<O>, format: F) -> Self {
+        Self {
+            decay,
+            format: format.into(),
+        }
+    }
+}
+
+impl<O: StdError> Display for DecayDisplay<'_, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let format = self.format.as_ref();
+        let depth = self.decay.iter().count();
+        let shown = format.max_depth.unwrap_or(usize::MAX).min(depth);
+        let omitted = depth - shown;
+        let levels = format.ordered(self.decay.iter()).take(shown);
+        let thread = self.decay.thread();
+        match format.separator.as_deref() {
+            None => {
+                let mut output = StructShow::new(f, Alternate::Inherit);
+                levels.for_each(|level| format.level_fields(&mut output, level, thread));
+                if omitted > 0 {
+                    output.field(&"omitted", &omitted);
+                }
+                output.finish()
+            }
+            Some(separator) => {
+                for (index, level) in levels.enumerate() {
+                    if index > 0 {
+                        f.write_str(separator)?;
+                    }
+                    let mut output = StructShow::new(f, Alternate::Inherit);
+                    format.level_fields(&mut output, level, thread);
+                    output.finish()?;
+                }
+                if omitted > 0 {
+                    if shown > 0 {
+                        f.write_str(separator)?;
+                    }
+                    StructShow::new(f, Alternate::Inherit)
+                        .field(&"omitted", &omitted)
+                        .finish()?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<O: StdError> Debug for DecayDisplay<'_, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+impl<O: StdError> Decay<O> {
+    /// Returns the view of error which is rendered according to the given format.
+    pub fn display_with<'a>(&'a self, format: &'a DecayFormat) -> DecayDisplay<'a, O> {
+        DecayDisplay::new(self, format)
+    }
+
+    /// Returns the view of error which is rendered according to the process-wide format
+    /// (see [DecayFormat::set_global]).
+    pub fn display_global(&self) -> DecayDisplay<'_, O> {
+        DecayDisplay::new(self, DecayFormat::global())
+    }
+}
+
+#[cfg(test)]
+mod tests;
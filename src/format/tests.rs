@@ -0,0 +1,326 @@
+use super::*;
+use crate::CrateInfo;
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[error("{0}")]
+struct TestErr(&'static str);
+
+type Fail = Decay<TestErr>;
+
+const WINDOWS_PLACE: CodePlace = CodePlace::new("C:\\work\\proj\\src\\main.rs", 27, 60);
+const UNIX_PLACE: CodePlace = CodePlace::new("/work/proj/src/lib.rs", 34, 87);
+const RELATIVE_PLACE: CodePlace = CodePlace::new("src/lib.rs", 38, 55);
+
+// Thread marks are left empty, so the output doesn't depend on the enabled features.
+fn unmarked_root(place: CodePlace, note: &'static str) -> Fail {
+    Decay::Internal {
+        note: note.into(),
+        place: CodePlaceChain::unmarked(place),
+    }
+}
+
+// The mark of the external root is taken from the first further level, so it is left empty as well.
+fn unmarked_external(error: TestErr, place: CodePlace, note: &'static str) -> Fail {
+    Decay::Further {
+        note: note.into(),
+        place: CodePlaceChain::unmarked(place),
+        error: Box::new(Decay::External { error }),
+    }
+}
+
+fn internal_fail() -> Fail {
+    unmarked_root(WINDOWS_PLACE, "Root note")
+        .further_unnoted(UNIX_PLACE)
+        .further(RELATIVE_PLACE, "Outer note")
+}
+
+fn external_fail() -> Fail {
+    unmarked_external(TestErr("External error"), RELATIVE_PLACE, "Outer note")
+}
+
+#[test]
+fn default_matches_debug() {
+    let fail = internal_fail();
+    let format = DecayFormat::default();
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        format!("{:?}", fail)
+    );
+    assert_eq!(
+        format!("{:#}", fail.display_with(&format)),
+        format!("{:#?}", fail)
+    );
+    let fail = external_fail();
+    assert_eq!(
+        format!("{:?}", fail.display_with(&format)),
+        format!("{:?}", fail)
+    );
+}
+
+#[test]
+fn normalized_paths() {
+    let fail = internal_fail();
+    let format = DecayFormat::new().with_normalized_paths(true);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [src/lib.rs:38:55], note: Outer note, place: [/work/proj/src/lib.rs:34:87, C:/work/proj/src/main.rs:27:60], note: Root note}"
+    );
+}
+
+#[test]
+fn stripped_root() {
+    let fail = internal_fail();
+    let format = DecayFormat::new().with_root("/work/proj");
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [src/lib.rs:38:55], note: Outer note, place: [src/lib.rs:34:87, C:\\work\\proj\\src\\main.rs:27:60], note: Root note}"
+    );
+    let format = DecayFormat::new()
+        .with_root("C:/work/proj/")
+        .with_normalized_paths(true);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [src/lib.rs:38:55], note: Outer note, place: [/work/proj/src/lib.rs:34:87, src/main.rs:27:60], note: Root note}"
+    );
+    let format = DecayFormat::new().with_root("/work/pro");
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        format!("{:?}", fail)
+    );
+}
+
+#[test]
+fn masked_places() {
+    let fail = external_fail();
+    let format = DecayFormat::new().with_mask(PlaceMask::Column);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [src/lib.rs:38:*], note: Outer note, error: External error}"
+    );
+    let format = DecayFormat::new().with_mask(PlaceMask::Position);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [src/lib.rs:*:*], note: Outer note, error: External error}"
+    );
+}
+
+#[test]
+fn notes_only() {
+    let format = DecayFormat::new().with_notes_only(true);
+    assert_eq!(
+        format!("{}", internal_fail().display_with(&format)),
+        "{note: Outer note, note: Root note}"
+    );
+    assert_eq!(
+        format!("{}", external_fail().display_with(&format)),
+        "{note: Outer note}"
+    );
+}
+
+#[test]
+fn snapshot() {
+    let format = DecayFormat::snapshot().with_root("C:\\work\\proj");
+    assert_eq!(
+        format!("{:#}", internal_fail().display_with(&format)),
+        r#"{
+    place: [src/lib.rs:*:*],
+    note: Outer note,
+    place: [/work/proj/src/lib.rs:*:*, src/main.rs:*:*],
+    note: Root note,
+}"#
+    );
+}
+
+fn deep_fail() -> Fail {
+    unmarked_root(RELATIVE_PLACE, "A pretty long root note")
+        .further_unnoted(UNIX_PLACE)
+        .further_unnoted(UNIX_PLACE)
+        .further_unnoted(UNIX_PLACE)
+        .further(WINDOWS_PLACE, "Middle note")
+        .further(RELATIVE_PLACE, "Outer note")
+}
+
+#[test]
+fn without_places() {
+    let format = DecayFormat::new().with_places(false);
+    assert_eq!(
+        format!("{}", external_fail().display_with(&format)),
+        "{note: Outer note, error: External error}"
+    );
+}
+
+#[test]
+fn innermost_first() {
+    let format = DecayFormat::new().with_order(DecayOrder::InnermostFirst);
+    assert_eq!(
+        format!("{}", internal_fail().display_with(&format)),
+        "{place: [C:\\work\\proj\\src\\main.rs:27:60, /work/proj/src/lib.rs:34:87], note: Root note, place: [src/lib.rs:38:55], note: Outer note}"
+    );
+    assert_eq!(
+        format!("{}", external_fail().display_with(&format)),
+        "{error: External error, place: [src/lib.rs:38:55], note: Outer note}"
+    );
+}
+
+#[test]
+fn separated_levels() {
+    let format = DecayFormat::new()
+        .with_separator(Some(" <- "))
+        .with_places(false);
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{note: Outer note} <- {note: Middle note} <- {note: A pretty long root note}"
+    );
+    assert_eq!(
+        format!("{:#}", external_fail().display_with(&format)),
+        "{\n    note: Outer note,\n} <- {\n    error: External error,\n}"
+    );
+}
+
+#[test]
+fn limited_depth() {
+    let format = DecayFormat::new()
+        .with_places(false)
+        .with_max_depth(Some(2));
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{note: Outer note, note: Middle note, omitted: 1}"
+    );
+    let format = format.with_separator(Some(" | ")).with_max_depth(Some(1));
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{note: Outer note} | {omitted: 2}"
+    );
+    let format = format.with_max_depth(Some(0));
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{omitted: 3}"
+    );
+    let format = format.with_max_depth(Some(3));
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{note: Outer note} | {note: Middle note} | {note: A pretty long root note}"
+    );
+}
+
+#[test]
+fn truncated_notes() {
+    let format = DecayFormat::new()
+        .with_places(false)
+        .with_max_note_len(Some(11));
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{note: Outer note, note: Middle note, note: A pretty lo…}"
+    );
+}
+
+#[test]
+fn collapsed_places() {
+    let format = DecayFormat::new().with_collapsed_places(true);
+    assert_eq!(
+        format!("{}", deep_fail().display_with(&format)),
+        "{place: [src/lib.rs:38:55], note: Outer note, place: [C:\\work\\proj\\src\\main.rs:27:60], note: Middle note, place: [/work/proj/src/lib.rs:34:87 (x3), src/lib.rs:38:55], note: A pretty long root note}"
+    );
+}
+
+const TEST_CRATE: CrateInfo = CrateInfo {
+    name: "proj",
+    manifest_dir: "/work/proj",
+};
+
+fn located_fail() -> Fail {
+    unmarked_root(
+        CodePlace::new("src/lib.rs", 34, 87)
+            .with_module(Some("proj::inner"))
+            .with_crate(Some(&TEST_CRATE)),
+        "Root note",
+    )
+    .further_unnoted(RELATIVE_PLACE)
+}
+
+#[test]
+fn absolute_paths() {
+    let format = DecayFormat::new().with_paths(PathStyle::Absolute);
+    assert_eq!(
+        format!("{}", located_fail().display_with(&format)),
+        "{place: [src/lib.rs:38:55, /work/proj/src/lib.rs:34:87], note: Root note}"
+    );
+}
+
+#[test]
+fn module_paths() {
+    let format = DecayFormat::new().with_paths(PathStyle::Module);
+    assert_eq!(
+        format!("{}", located_fail().display_with(&format)),
+        "{place: [src/lib.rs:38:55, proj::inner:34:87], note: Root note}"
+    );
+}
+
+#[test]
+fn hyperlink_paths() {
+    let format = DecayFormat::new().with_paths(PathStyle::Hyperlink);
+    assert_eq!(
+        format!("{}", located_fail().display_with(&format)),
+        "{place: [src/lib.rs:38:55, \x1b]8;;file:///work/proj/src/lib.rs\x1b\\src/lib.rs:34:87\x1b]8;;\x1b\\], note: Root note}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn hyperlink_uris() {
+    let fail = unmarked_root(
+        CodePlace::new("/work/my proj/#1/lib.rs", 34, 87),
+        "Root note",
+    );
+    let format = DecayFormat::new().with_paths(PathStyle::Hyperlink);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [\x1b]8;;file:///work/my%20proj/%231/lib.rs\x1b\\/work/my proj/#1/lib.rs:34:87\x1b]8;;\x1b\\], note: Root note}"
+    );
+}
+
+#[test]
+fn function_places() {
+    let fail = unmarked_root(
+        CodePlace::new("src/lib.rs", 34, 87)
+            .with_module(Some("proj::inner"))
+            .with_function(Some("do_something")),
+        "Root note",
+    );
+    let format = DecayFormat::new().with_mask(PlaceMask::Position);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [proj::inner::do_something (src/lib.rs:*:*)], note: Root note}"
+    );
+    let format = DecayFormat::new().with_paths(PathStyle::Module);
+    assert_eq!(
+        format!("{}", fail.display_with(&format)),
+        "{place: [proj::inner::do_something:34:87], note: Root note}"
+    );
+}
+
+#[cfg(feature = "thread-info")]
+#[test]
+fn thread_marks() {
+    let fail = std::thread::Builder::new()
+        .name("cadom-worker".into())
+        .spawn(|| Fail::new(RELATIVE_PLACE, "Root note").further(UNIX_PLACE, "Outer note"))
+        .expect("Thread should be spawned")
+        .join()
+        .expect("Thread should not panic");
+    let thread = fail.thread().clone();
+    assert_eq!(thread.thread_name(), Some("cadom-worker"));
+    let expected = format!(
+        "{{place: [/work/proj/src/lib.rs:34:87], note: Outer note, place: [src/lib.rs:38:55], note: Root note, thread: {}}}",
+        thread
+    );
+    assert_eq!(format!("{:?}", fail), expected);
+    assert_eq!(
+        format!("{}", fail.display_with(&DecayFormat::new())),
+        expected
+    );
+    assert_eq!(
+        format!("{}", fail.display_with(&DecayFormat::new().with_threads(false))),
+        "{place: [/work/proj/src/lib.rs:34:87], note: Outer note, place: [src/lib.rs:38:55], note: Root note}"
+    );
+}
@@ -0,0 +1,178 @@
+use futures_core::{Stream, TryFuture, TryStream};
+use pin_project_lite::pin_project;
+use std::{
+    error::Error as StdError,
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{CodePlace, Decay, IntoDecay};
+
+/// Extension of fallible futures turning their errors into [Decay].
+/// Places are made from the [Location] of the combinator call, so they record neither
+/// the module nor the function.
+pub trait RotFutureExt: TryFuture + Sized {
+    /// Wraps the future so its error (if any) is turned into [Decay] with the place
+    /// of this call added, just like `.map_err(rot!())` does right after the `.await`.
+    #[track_caller]
+    fn rot<O: StdError, const L: usize>(self) -> RotFuture<Self, Self::Error, O>
+    where
+        Self::Error: IntoDecay<O, L>,
+    {
+        RotFuture {
+            inner: self,
+            place: Some(Location::caller().into()),
+            convert: IntoDecay::into_decay,
+        }
+    }
+}
+
+impl<F: TryFuture> RotFutureExt for F {}
+
+/// Extension of fallible streams turning their errors into [Decay].
+/// Places are made from the [Location] of the combinator call, so they record neither
+/// the module nor the function.
+pub trait RotStreamExt: TryStream + Sized {
+    /// Wraps the stream so every its error is turned into [Decay] with the place
+    /// of this call added.
+    #[track_caller]
+    fn rot<O: StdError, const L: usize>(self) -> RotStream<Self, Self::Error, O>
+    where
+        Self::Error: IntoDecay<O, L>,
+    {
+        RotStream {
+            inner: self,
+            place: Location::caller().into(),
+            convert: IntoDecay::into_decay,
+        }
+    }
+}
+
+impl<S: TryStream> RotStreamExt for S {}
+
+pin_project! {
+    /// Future returned by [RotFutureExt::rot].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct RotFuture<F, E, O: StdError> {
+        #[pin]
+        inner: F,
+        place: Option<CodePlace>,
+        convert: fn(E) -> Decay<O>,
+    }
+}
+
+impl<F: TryFuture, O: StdError> Future for RotFuture<F, F::Error, O> {
+    type Output = Result<F::Ok, Decay<O>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.inner.try_poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(value)) => Poll::Ready(Ok(value)),
+            Poll::Ready(Err(error)) => {
+                let place = this
+                    .place
+                    .take()
+                    .expect("RotFuture should not be polled after completion");
+                Poll::Ready(Err((this.convert)(error).further_unnoted(place)))
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Stream returned by [RotStreamExt::rot].
+    #[must_use = "streams do nothing unless polled"]
+    pub struct RotStream<S, E, O: StdError> {
+        #[pin]
+        inner: S,
+        place: CodePlace,
+        convert: fn(E) -> Decay<O>,
+    }
+}
+
+impl<S: TryStream, O: StdError> Stream for RotStream<S, S::Error, O> {
+    type Item = Result<S::Ok, Decay<O>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        match this.inner.try_poll_next(cx) {
+            Poll::Ready(Some(Err(error))) => Poll::Ready(Some(Err(
+                (this.convert)(error).further_unnoted(this.place.clone())
+            ))),
+            Poll::Ready(Some(Ok(value))) => Poll::Ready(Some(Ok(value))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::futures::{
+        executor::block_on,
+        future::{ready, FutureExt as _},
+        stream::{iter, StreamExt as _},
+    };
+
+    use super::*;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Test error #{0}")]
+    struct TestErr(u8);
+
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn future_ok() {
+        let result: Result<u8, Fail> = block_on(ready(Ok::<u8, TestErr>(42)).rot());
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn future_err() {
+        let (future, line) = (ready(Err::<u8, TestErr>(TestErr(1))).rot(), line!());
+        let result: Result<u8, Fail> = block_on(future);
+        assert_eq!(
+            result,
+            Err(Fail::from(TestErr(1)).further_unnoted(CodePlace::new("src/futures.rs", line, 69)))
+        );
+    }
+
+    #[test]
+    fn future_err_from_decay() {
+        let future = async { Err::<u8, Fail>(decay!("Root note")) }.boxed();
+        let (future, line) = (future.rot(), line!());
+        let result: Result<u8, Fail> = block_on(future);
+        let places = match &result {
+            Err(Decay::Internal { place, .. }) => place.iter().cloned().collect::<Vec<_>>(),
+            _ => panic!("Result should be Err(Decay::Internal)"),
+        };
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0], CodePlace::new("src/futures.rs", line, 38));
+    }
+
+    #[test]
+    fn stream_items() {
+        let (stream, line) = (iter(vec![Ok(1), Err(TestErr(2)), Ok(3)]).rot(), line!());
+        let items: Vec<Result<u8, Fail>> = block_on(stream.collect());
+        assert_eq!(
+            items,
+            vec![
+                Ok(1),
+                Err(Fail::from(TestErr(2)).further_unnoted(CodePlace::new(
+                    "src/futures.rs",
+                    line,
+                    73
+                ))),
+                Ok(3),
+            ]
+        );
+    }
+}
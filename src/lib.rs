@@ -6,12 +6,64 @@ mod place;
 mod note;
 #[macro_use]
 mod decay;
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
+mod fingerprint;
+mod format;
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+pub mod futures;
+#[cfg(feature = "log")]
+mod log;
+mod origin;
+mod panic;
+#[cfg(feature = "pyo3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pyo3")))]
+pub mod python;
+#[cfg(feature = "sentry")]
+mod sentry;
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 mod serde;
+mod snippet;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+mod thread;
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
 
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use self::log::__log_decay;
 #[cfg(feature = "serde")]
-pub use self::serde::{DecayDeser, DecayDeserInner, DecayDeserItem};
-pub use decay::{Decay, DecayRoot, IntoDecay};
-pub use note::Note;
-pub use place::{CodePlace, CodePlaceChain};
+pub use self::serde::{
+    serialize_innermost_first, serialize_templated, DecayDeser, DecayDeserInner, DecayDeserItem,
+};
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use cadom_macros::__templated_note;
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub use cadom_macros::{instrument, Origin};
+pub use decay::{Decay, DecayIter, DecayRoot, IntoDecay, SyncDecay, SyncOrigin};
+pub use format::{DecayDisplay, DecayFormat, DecayOrder, PathStyle, PlaceMask};
+pub use note::{Note, NoteArgs, NoteInterner};
+pub use origin::Origin;
+pub use panic::{catch, install_panic_hook, PanicHook, PanicOrigin};
+#[doc(hidden)]
+pub use place::__function_name;
+pub use place::{CodePlace, CodePlaceChain, CodePlaceChainIter, CrateInfo};
+#[cfg(feature = "sentry")]
+pub use sentry::SentryEvent;
+pub use snippet::DecaySnippets;
+pub use thread::ThreadMark;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "schema")]
+    pub use ::schemars;
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+}
@@ -0,0 +1,223 @@
+use cubob::display_list_from_iter;
+use log::{
+    kv::{Error as KvError, Key, Source, Value, VisitSource},
+    Level, Metadata, Record,
+};
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{Decay, DecayRoot};
+
+/// Notes of all levels, from the outermost one.
+struct Notes<'a, O: StdError>(&'a Decay<O>);
+
+impl<O: StdError> Display for Notes<'_, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let notes = self.0.iter().filter_map(|level| match level {
+            Decay::Internal { note, .. } | Decay::Further { note, .. } => note.text(),
+            Decay::External { .. } => None,
+        });
+        display_list_from_iter(f, notes)
+    }
+}
+
+/// Places of all levels, from the outermost one.
+struct Places<'a, O: StdError>(&'a Decay<O>);
+
+impl<O: StdError> Display for Places<'_, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let places = self.0.iter().flat_map(|level| match level {
+            Decay::Internal { place, .. } | Decay::Further { place, .. } => Some(place),
+            Decay::External { .. } => None,
+        });
+        display_list_from_iter(f, places.flatten())
+    }
+}
+
+/// Key-value fields of the log record: `error` (the origin, for the external decay only),
+/// `notes` and `places`.
+struct Fields<'a, O: StdError> {
+    error: Option<&'a O>,
+    notes: Notes<'a, O>,
+    places: Places<'a, O>,
+}
+
+impl<O: StdError> Source for Fields<'_, O> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), KvError> {
+        if let Some(error) = self.error {
+            visitor.visit_pair(Key::from_str("error"), Value::from_dyn_display(error))?;
+        }
+        visitor.visit_pair(Key::from_str("notes"), Value::from_display(&self.notes))?;
+        visitor.visit_pair(Key::from_str("places"), Value::from_display(&self.places))
+    }
+}
+
+#[doc(hidden)]
+pub fn __log_decay<O: StdError>(
+    decay: &Decay<O>,
+    level: Level,
+    target: &str,
+    module_path: &'static str,
+    file: &'static str,
+    line: u32,
+) {
+    let logger = log::logger();
+    let metadata = Metadata::builder().level(level).target(target).build();
+    if level > log::max_level() || !logger.enabled(&metadata) {
+        return;
+    }
+    let fields = Fields {
+        error: match decay.root() {
+            DecayRoot::External { error } => Some(error),
+            DecayRoot::Internal { .. } => None,
+        },
+        notes: Notes(decay),
+        places: Places(decay),
+    };
+    logger.log(
+        &Record::builder()
+            .metadata(metadata)
+            .args(format_args!("{}", decay.headline()))
+            .module_path_static(Some(module_path))
+            .file_static(Some(file))
+            .line(Some(line))
+            .key_values(&fields)
+            .build(),
+    );
+}
+
+/// Logs the [Decay] through the `log` crate. The message is the outermost note (or the origin
+/// message), while the origin, notes and places go as `error`, `notes` and `places` key-value fields.
+/// The target defaults to the current module, just like with `log` macros.
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+#[macro_export]
+macro_rules! log_decay {
+    (target: $target:expr, $level:expr, $decay:expr $(,)?) => {
+        $crate::__log_decay($decay, $level, $target, module_path!(), file!(), line!())
+    };
+
+    ($level:expr, $decay:expr $(,)?) => {
+        $crate::log_decay!(target: module_path!(), $level, $decay)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{kv::VisitSource, LevelFilter, Log};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::CodePlace;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection reset")]
+    struct TestErr;
+
+    type Fail = Decay<TestErr>;
+
+    /// Record reduced to the parts checked by tests.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Captured {
+        level: Level,
+        target: String,
+        message: String,
+        line: Option<u32>,
+        fields: Vec<(String, String)>,
+    }
+
+    struct Capture(Mutex<Vec<Captured>>);
+
+    struct Pairs(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Pairs {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() <= Level::Warn
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            let mut fields = Pairs(Vec::new());
+            record
+                .key_values()
+                .visit(&mut fields)
+                .expect("Fields should be visited");
+            self.0
+                .lock()
+                .expect("Capture should be available")
+                .push(Captured {
+                    level: record.level(),
+                    target: record.target().to_owned(),
+                    message: record.args().to_string(),
+                    line: record.line(),
+                    fields: fields.0,
+                });
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+    fn pairs(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn key_value_fields() {
+        log::set_logger(&CAPTURE).expect("Logger should be set once");
+        log::set_max_level(LevelFilter::Trace);
+
+        let external = Fail::from(TestErr)
+            .further(
+                CodePlace::new("src/net.rs", 12, 5),
+                "Can't read the response",
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(CodePlace::new("src/main.rs", 7, 1), "Request failed");
+        let internal = Fail::new_unnoted(CodePlace::new("src/config.rs", 3, 14));
+
+        let line = line!() + 1;
+        log_decay!(Level::Error, &external);
+        log_decay!(target: "config", Level::Warn, &internal);
+        log_decay!(Level::Info, &external);
+
+        let captured = CAPTURE.0.lock().expect("Capture should be available");
+        assert_eq!(
+            *captured,
+            vec![
+                Captured {
+                    level: Level::Error,
+                    target: module_path!().to_owned(),
+                    message: "Request failed".to_owned(),
+                    line: Some(line),
+                    fields: pairs(&[
+                        ("error", "Connection reset"),
+                        ("notes", "[Request failed, Can't read the response]"),
+                        (
+                            "places",
+                            "[src/main.rs:7:1, src/client.rs:40:9, src/net.rs:12:5]"
+                        ),
+                    ]),
+                },
+                Captured {
+                    level: Level::Warn,
+                    target: "config".to_owned(),
+                    message: String::new(),
+                    line: Some(line + 1),
+                    fields: pairs(&[("notes", "[]"), ("places", "[src/config.rs:3:14]")]),
+                },
+            ]
+        );
+    }
+}
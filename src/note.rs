@@ -1,50 +1,288 @@
 use std::{
     borrow::{Borrow, Cow},
+    collections::HashSet,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
+    iter::FromIterator,
     ops::Deref,
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Arc, Mutex, OnceLock,
+    },
 };
 
 pub type StaticCowStr = Cow<'static, str>;
 
-fn opt_text<T: AsRef<str>>(val: T) -> Option<T> {
+pub(crate) fn opt_text<T: AsRef<str>>(val: T) -> Option<T> {
     match val.as_ref().is_empty() {
         true => None,
         false => Some(val),
     }
 }
 
-#[derive(Clone, Eq)]
-pub struct Note(Option<StaticCowStr>);
+struct DeferredNote {
+    source: Box<dyn Display + Send + Sync>,
+    rendered: OnceLock<Option<StaticCowStr>>,
+}
+
+impl DeferredNote {
+    fn rendered(&self) -> &Option<StaticCowStr> {
+        self.rendered
+            .get_or_init(|| opt_text(self.source.to_string()).map(StaticCowStr::Owned))
+    }
+}
+
+struct TemplatedNote {
+    template: &'static str,
+    args: NoteArgs,
+    text: Option<StaticCowStr>,
+}
+
+/// Format arguments of the note made from the format string (see [Note::args]): names
+/// (indices for positional ones) along with text representations, formatted the way
+/// the format string does it, in the order they were given (implicitly captured ones go last).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NoteArgs(Vec<(&'static str, String)>);
+
+impl NoteArgs {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn push(&mut self, name: &'static str, value: String) {
+        self.0.push((name, value))
+    }
+
+    /// Returns the text representation of the argument with the given name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find_map(|(known, value)| (known == name).then_some(value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> + '_ {
+        self.0.iter().map(|(name, value)| (*name, value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<const N: usize> From<[(&'static str, String); N]> for NoteArgs {
+    fn from(args: [(&'static str, String); N]) -> Self {
+        Self(args.into())
+    }
+}
+
+impl FromIterator<(&'static str, String)> for NoteArgs {
+    fn from_iter<I: IntoIterator<Item = (&'static str, String)>>(args: I) -> Self {
+        Self(args.into_iter().collect())
+    }
+}
+
+/// Copy of the shared text made on the first dereference of the note. It's kept behind
+/// a single pointer, so the note stays as small as it is with other kinds of texts.
+#[derive(Default)]
+struct TextCopy(AtomicPtr<Option<StaticCowStr>>);
+
+impl TextCopy {
+    fn get_or_copy(&self, text: &str) -> &Option<StaticCowStr> {
+        let mut copy = self.0.load(Ordering::Acquire);
+        if copy.is_null() {
+            let made = Box::into_raw(Box::new(Some(StaticCowStr::Owned(text.to_owned()))));
+            copy = match self.0.compare_exchange(
+                ptr::null_mut(),
+                made,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => made,
+                Err(existing) => {
+                    // Another thread has made the copy first.
+                    // SAFETY: `made` comes from `Box::into_raw` above and was never shared.
+                    drop(unsafe { Box::from_raw(made) });
+                    existing
+                }
+            };
+        }
+        // SAFETY: the pointer is set only once, from `Box::into_raw`, and is freed only on drop.
+        unsafe { &*copy }
+    }
+}
+
+impl Drop for TextCopy {
+    fn drop(&mut self) {
+        let copy = *self.0.get_mut();
+        if !copy.is_null() {
+            // SAFETY: the pointer comes from `Box::into_raw` and nothing borrows it anymore.
+            drop(unsafe { Box::from_raw(copy) });
+        }
+    }
+}
+
+struct FnDisplay<F>(F);
+
+impl<F: Fn(&mut Formatter<'_>) -> FmtResult> Display for FnDisplay<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        (self.0)(f)
+    }
+}
+
+enum NoteInner {
+    /// Static text (or its absence), which is cheap to clone as is.
+    Text(Option<StaticCowStr>),
+    /// Owned text (always present), shared by all clones of the note.
+    Shared(Arc<Option<StaticCowStr>>),
+    /// Text (always present) shared with whoever has given it, along with its copy
+    /// made only if the note is dereferenced.
+    ArcText(Arc<str>, TextCopy),
+    Deferred(Arc<DeferredNote>),
+    Templated(Arc<TemplatedNote>),
+}
+
+impl Clone for NoteInner {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Text(text) => Self::Text(text.clone()),
+            Self::Shared(text) => Self::Shared(text.clone()),
+            Self::ArcText(text, _) => Self::ArcText(text.clone(), TextCopy::default()),
+            Self::Deferred(deferred) => Self::Deferred(deferred.clone()),
+            Self::Templated(templated) => Self::Templated(templated.clone()),
+        }
+    }
+}
+
+/// Optional text describing some level of [Decay](crate::Decay). The text can be given either
+/// directly or as some [Display] value, which is formatted only on the first access to the text
+/// (rendering, comparison, serialization etc.) and then is cached.
+/// Cloning the note never copies the text: owned texts are shared by all clones,
+/// and texts given as [`Arc<str>`] are shared with the caller too.
+/// Notes made by macros like `rot!` from the format string also keep
+/// the format template and the arguments (see [Note::template] and [Note::args]).
+#[derive(Clone)]
+pub struct Note(NoteInner);
 
 impl Note {
-    pub const NONE: Self = Self(None);
+    pub const NONE: Self = Self(NoteInner::Text(None));
+
+    fn from_text(text: Option<StaticCowStr>) -> Self {
+        match text {
+            text @ Some(StaticCowStr::Owned(_)) => Self(NoteInner::Shared(Arc::new(text))),
+            text => Self(NoteInner::Text(text)),
+        }
+    }
+
+    /// Creates the note which formats the given value only when its text is needed.
+    /// Since the note is not rendered until then, [Decay::further](crate::Decay::further)
+    /// treats it as a set one and adds a new level even if the text turns out to be empty.
+    pub fn deferred<D: Display + Send + Sync + 'static>(source: D) -> Self {
+        Self(NoteInner::Deferred(Arc::new(DeferredNote {
+            source: Box::new(source),
+            rendered: OnceLock::new(),
+        })))
+    }
+
+    /// Creates the note which calls the given closure only when its text is needed.
+    pub fn from_fn<F>(source: F) -> Self
+    where
+        F: Fn(&mut Formatter<'_>) -> FmtResult + Send + Sync + 'static,
+    {
+        Self::deferred(FnDisplay(source))
+    }
+
+    /// Creates the note with the already rendered text, which also keeps the format template
+    /// and the named text representations of its arguments.
+    pub fn templated<T: Into<StaticCowStr> + AsRef<str>>(
+        template: &'static str,
+        args: NoteArgs,
+        text: T,
+    ) -> Self {
+        Self(NoteInner::Templated(Arc::new(TemplatedNote {
+            template,
+            args,
+            text: opt_text(text).map(Into::into),
+        })))
+    }
+
+    /// Returns the note from the process-wide [NoteInterner::global], so equal texts share the same storage.
+    pub fn interned(text: &str) -> Self {
+        NoteInterner::global().intern(text)
+    }
 
     pub fn text(&self) -> Option<&str> {
-        self.0.as_ref().map(Borrow::borrow)
+        match &self.0 {
+            NoteInner::ArcText(text, _) => Some(text),
+            _ => self.deref().as_ref().map(Borrow::borrow),
+        }
+    }
+
+    /// Returns the format template of the note made from the format string.
+    pub fn template(&self) -> Option<&'static str> {
+        match &self.0 {
+            NoteInner::Templated(templated) => Some(templated.template),
+            _ => None,
+        }
+    }
+
+    /// Returns arguments of the note made from the format string (empty for other notes).
+    /// Macros record them only with the `macros` feature, which parses the format string.
+    pub fn args(&self) -> &NoteArgs {
+        static NO_ARGS: NoteArgs = NoteArgs::new();
+        match &self.0 {
+            NoteInner::Templated(templated) => &templated.args,
+            _ => &NO_ARGS,
+        }
+    }
+
+    /// Checks if the note is known to be empty without rendering it: the deferred note
+    /// which is not rendered yet is assumed to be set, even if it renders empty later.
+    pub(crate) fn is_unset(&self) -> bool {
+        match &self.0 {
+            NoteInner::Text(text) => text.is_none(),
+            NoteInner::Shared(_) | NoteInner::ArcText(..) => false,
+            NoteInner::Deferred(deferred) => matches!(deferred.rendered.get(), Some(None)),
+            NoteInner::Templated(templated) => templated.text.is_none(),
+        }
     }
 }
 
 impl From<StaticCowStr> for Note {
     fn from(src: StaticCowStr) -> Self {
-        Self(opt_text(src))
+        Self::from_text(opt_text(src))
     }
 }
 
 impl From<String> for Note {
     fn from(src: String) -> Self {
-        Self(opt_text(src).map(StaticCowStr::Owned))
+        Self::from_text(opt_text(src).map(StaticCowStr::Owned))
     }
 }
 
 impl From<&'static str> for Note {
     fn from(src: &'static str) -> Self {
-        Self(opt_text(src).map(StaticCowStr::Borrowed))
+        Self(NoteInner::Text(opt_text(src).map(StaticCowStr::Borrowed)))
+    }
+}
+
+/// The text is not copied: it's shared with the given [Arc] (so making notes from clones
+/// of the same [`Arc<str>`] doesn't allocate). Only dereferencing the note to [`Option<StaticCowStr>`]
+/// copies the text, once per note.
+impl From<Arc<str>> for Note {
+    fn from(src: Arc<str>) -> Self {
+        match src.is_empty() {
+            true => Self::NONE,
+            false => Self(NoteInner::ArcText(src, TextCopy::default())),
+        }
     }
 }
 
 impl<S: Into<StaticCowStr> + AsRef<str>> From<Option<S>> for Note {
     fn from(src: Option<S>) -> Self {
-        Self(src.and_then(opt_text).map(Into::into))
+        Self::from_text(src.and_then(opt_text).map(Into::into))
     }
 }
 
@@ -52,15 +290,21 @@ impl Deref for Note {
     type Target = Option<StaticCowStr>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        match &self.0 {
+            NoteInner::Text(text) => text,
+            NoteInner::Shared(text) => text,
+            NoteInner::ArcText(text, copy) => copy.get_or_copy(text),
+            NoteInner::Deferred(deferred) => deferred.rendered(),
+            NoteInner::Templated(templated) => &templated.text,
+        }
     }
 }
 
 impl Display for Note {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        match &self.0 {
+        match self.text() {
             None => write!(f, "``"),
-            Some(text) => Display::fmt(&text, f),
+            Some(text) => Display::fmt(text, f),
         }
     }
 }
@@ -73,19 +317,72 @@ impl Debug for Note {
 
 impl PartialEq for Note {
     fn eq(&self, other: &Self) -> bool {
-        match (&self.0, &other.0) {
-            (None, None) => true,
-            (Some(t1), Some(t2)) => t1.as_ref() == t2.as_ref(),
-            _ => false,
-        }
+        self.text() == other.text()
     }
 }
 
+impl Eq for Note {}
+
 impl PartialEq<&str> for Note {
     fn eq(&self, other: &&str) -> bool {
-        match self.0 {
+        match self.text() {
             None => other.is_empty(),
-            Some(ref text) => text == other,
+            Some(text) => text == *other,
+        }
+    }
+}
+
+/// Storage of shared note texts: every text is allocated only once and then shared
+/// by all notes produced from it. Since the stored texts are never evicted automatically,
+/// it suits a limited set of repeating texts, otherwise [NoteInterner::clear] should be called sometimes.
+#[derive(Default)]
+pub struct NoteInterner(Mutex<HashSet<Arc<str>>>);
+
+impl NoteInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide interner used by [Note::interned].
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<NoteInterner> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    pub fn intern(&self, text: &str) -> Note {
+        if text.is_empty() {
+            return Note::NONE;
+        }
+        let mut texts = match self.0.lock() {
+            Ok(texts) => texts,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let shared = match texts.get(text) {
+            Some(interned) => interned.clone(),
+            None => {
+                let shared = Arc::<str>::from(text);
+                texts.insert(shared.clone());
+                shared
+            }
+        };
+        Note::from(shared)
+    }
+
+    pub fn len(&self) -> usize {
+        match self.0.lock() {
+            Ok(texts) => texts.len(),
+            Err(poisoned) => poisoned.into_inner().len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        match self.0.lock() {
+            Ok(mut texts) => texts.clear(),
+            Err(poisoned) => poisoned.into_inner().clear(),
         }
     }
 }
@@ -101,7 +398,18 @@ macro_rules! note {
     };
 
     ($format:expr, $($rest:tt)*) => {
-        $crate::Note::from(format!($format, $($rest)*))
+        $crate::__templated_note!($crate, $format, $($rest)*)
+    };
+}
+
+/// Makes the note keeping the format template only: arguments are recorded by the procedural
+/// macro of the `macros` feature, which parses the format string.
+#[cfg(not(feature = "macros"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __templated_note {
+    ($krate:tt, $format:expr, $($rest:tt)*) => {
+        $crate::Note::templated($format, $crate::NoteArgs::new(), format!($format, $($rest)*))
     };
 }
 
@@ -119,4 +427,209 @@ mod tests {
             "Just some dynamic text"
         );
     }
+
+    #[test]
+    fn deferred() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+        let note = Note::from_fn(|f| {
+            RENDERS.fetch_add(1, Ordering::SeqCst);
+            write!(f, "Deferred text #{}", 1)
+        });
+        let cloned = note.clone();
+        assert!(!note.is_unset());
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 0);
+        assert_eq!(note, "Deferred text #1");
+        assert_eq!(note.text(), Some("Deferred text #1"));
+        assert_eq!(format!("{}", cloned), "Deferred text #1");
+        eprintln!(
+            "SIZE note {} decay {}",
+            std::mem::size_of::<Note>(),
+            std::mem::size_of::<crate::Decay<std::fmt::Error>>()
+        );
+        assert_eq!(cloned, note!("Deferred text #1"));
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shared() {
+        let text = Arc::<str>::from("Shared text");
+        let note = Note::from(text.clone());
+        assert_eq!(note, "Shared text");
+        let cloned = note.clone();
+        assert!(std::ptr::eq(note.text().unwrap(), &*text));
+        assert!(std::ptr::eq(cloned.text().unwrap(), &*text));
+        assert_eq!(cloned, note!("Shared text"));
+        assert_eq!(cloned.deref().as_deref(), Some("Shared text"));
+        assert_eq!(Note::from(Arc::<str>::from("")), Note::NONE);
+
+        let note = note!("Owned text".to_owned());
+        assert!(std::ptr::eq(note.deref(), note.clone().deref()));
+    }
+
+    #[test]
+    fn interned() {
+        let interner = NoteInterner::new();
+        let first = interner.intern(&format!("Connection to shard {} lost", 12));
+        let second = interner.intern(&format!("Connection to shard {} lost", 12));
+        let third = interner.intern("Connection to shard 13 lost");
+        assert_eq!(interner.intern(""), Note::NONE);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+        match (&first.0, &second.0) {
+            (NoteInner::ArcText(t1, _), NoteInner::ArcText(t2, _)) => assert!(Arc::ptr_eq(t1, t2)),
+            _ => panic!("Interned notes should be shared"),
+        }
+        interner.clear();
+        assert!(interner.is_empty());
+        assert_eq!(
+            Note::interned("Globally interned"),
+            Note::interned("Globally interned")
+        );
+        assert!(!NoteInterner::global().is_empty());
+        NoteInterner::global().clear();
+        assert!(NoteInterner::global().is_empty());
+    }
+
+    #[test]
+    fn deferred_empty() {
+        let note = Note::deferred(String::new());
+        assert!(!note.is_unset());
+        assert_eq!(note, Note::NONE);
+        assert_eq!(note, "");
+        assert!(note.is_none());
+        assert!(note.is_unset());
+        assert_eq!(format!("{:?}", note), "``");
+    }
+
+    #[test]
+    fn templated() {
+        #[allow(dead_code)]
+        #[derive(Debug)]
+        struct Role(&'static str);
+
+        let mut evaluations = 0;
+        let mut next_id = || {
+            evaluations += 1;
+            42
+        };
+        let user = "alice";
+        let note = note!(
+            "User {} ({}) with role {:?} not found in {shard}",
+            user,
+            next_id(),
+            Role("admin"),
+            shard = 7,
+        );
+        assert_eq!(evaluations, 1);
+        assert_eq!(
+            note,
+            r#"User alice (42) with role Role("admin") not found in 7"#
+        );
+        assert_eq!(
+            note.template(),
+            Some("User {} ({}) with role {:?} not found in {shard}")
+        );
+        #[cfg(feature = "macros")]
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [
+                ("0", "alice"),
+                ("1", "42"),
+                ("2", r#"Role("admin")"#),
+                ("shard", "7"),
+            ]
+        );
+        #[cfg(feature = "macros")]
+        assert_eq!(note.args().get("shard"), Some("7"));
+        #[cfg(not(feature = "macros"))]
+        assert!(note.args().is_empty());
+        assert_eq!(note.clone().template(), note.template());
+
+        let plain = note!("User not found");
+        assert_eq!(plain.template(), None);
+        assert!(plain.args().is_empty());
+        assert_eq!(note!("{}", ""), Note::NONE);
+    }
+
+    #[test]
+    fn templated_captured() {
+        let (id, width, level) = (7, 4, "warn");
+        let note = note!("Item {id:>width$} at {} level, id {id}", level);
+        assert_eq!(note, "Item    7 at warn level, id 7");
+        #[cfg(feature = "macros")]
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [("0", "warn"), ("id", "7"), ("width", "4")]
+        );
+
+        let note = note!("{{id}} {id} {{{level}}}", id = 8);
+        assert_eq!(note, "{id} 8 {warn}");
+        #[cfg(feature = "macros")]
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [("id", "8"), ("level", "warn")]
+        );
+
+        let fail: crate::Decay<std::fmt::Error> = crate::rot!("{} {id}", level)(std::fmt::Error);
+        let note = match fail {
+            crate::Decay::Further { note, .. } => note,
+            _ => panic!("Expected Decay::Further"),
+        };
+        assert_eq!(note, "warn 7");
+        #[cfg(feature = "macros")]
+        assert_eq!(note.args().get("id"), Some("7"));
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn templated_specs() {
+        struct Mask(u8);
+
+        impl std::fmt::LowerHex for Mask {
+            fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+                std::fmt::LowerHex::fmt(&self.0, f)
+            }
+        }
+
+        let (value, mask) = (3, Mask(0x2a));
+        let reference = &value;
+        let note = note!(
+            "{:?} and {}, {1:?}, {:#x} at {:p}",
+            "quoted",
+            value,
+            mask,
+            reference
+        );
+        let address = format!("{:p}", reference);
+        assert_eq!(
+            note,
+            format!(r#""quoted" and 3, 3, 0x2a at {}"#, address).as_str()
+        );
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [
+                ("0", r#""quoted""#),
+                ("1", "3"),
+                ("2", "0x2a"),
+                ("3", address.as_str())
+            ]
+        );
+
+        let note = note!("{} {}", value, value);
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [("0", "3"), ("1", "3")]
+        );
+
+        let note = note!("{:.*} {}", 2, 1.5, value);
+        assert_eq!(note, "1.50 3");
+        assert_eq!(
+            note.args().iter().collect::<Vec<_>>(),
+            [("0", "2"), ("1", "1.5"), ("2", "3")]
+        );
+    }
 }
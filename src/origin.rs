@@ -0,0 +1,49 @@
+use std::error::Error as StdError;
+
+use crate::{Decay, DecayRoot};
+
+/// Error type which is used as the origin (the `O` type) of [Decay] and which provides
+/// the classification code for every its value. Usually derived with `#[derive(cadom::Origin)]`.
+pub trait Origin: StdError {
+    /// Returns the short static code classifying this error (like `parse_int`).
+    fn code(&self) -> &'static str;
+}
+
+impl<O: Origin> Decay<O> {
+    /// Returns the classification code of the root error, if the root is an external one.
+    pub fn code(&self) -> Option<&'static str> {
+        match self.root() {
+            DecayRoot::External { error } => Some(error.code()),
+            DecayRoot::Internal { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Test error")]
+    struct TestErr;
+
+    impl Origin for TestErr {
+        fn code(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn external_code() {
+        let fail: Fail = rot!("Some note")(TestErr);
+        assert_eq!(fail.code(), Some("test"));
+    }
+
+    #[test]
+    fn internal_code() {
+        let fail: Fail = rot!("Some note")(decay!("Root note"));
+        assert_eq!(fail.code(), None);
+    }
+}
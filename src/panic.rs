@@ -0,0 +1,246 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+    panic::{self, Location, PanicHookInfo, UnwindSafe},
+    sync::Once,
+};
+
+use crate::{
+    place::{static_file, UNKNOWN_FILE},
+    CodePlace, Decay, DecayFormat, Note,
+};
+
+/// Origin of [Decay] made from panics. Such decay is always internal, so there are no values
+/// of this type at all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicOrigin {}
+
+impl Display for PanicOrigin {
+    fn fmt(&self, _: &mut Formatter<'_>) -> FmtResult {
+        match *self {}
+    }
+}
+
+impl StdError for PanicOrigin {}
+
+#[cfg(feature = "serde")]
+impl Serialize for PanicOrigin {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        match *self {}
+    }
+}
+
+impl<O: StdError> Decay<O> {
+    /// Makes the internal decay rooted at the panic location, with the panic message as the note
+    /// (if the payload is a string).
+    pub fn from_panic(info: &PanicHookInfo<'_>) -> Self {
+        Self::new(panic_place(info), payload_note(info.payload()))
+    }
+}
+
+fn panic_place(info: &PanicHookInfo<'_>) -> CodePlace {
+    match info.location() {
+        Some(location) => CodePlace::new(
+            static_file(location.file()),
+            location.line(),
+            location.column(),
+        ),
+        None => CodePlace::new(UNKNOWN_FILE, 0, 0),
+    }
+}
+
+fn payload_note(payload: &(dyn Any + Send)) -> Note {
+    match payload.downcast_ref::<&'static str>() {
+        Some(text) => Note::from(*text),
+        None => Note::from(payload.downcast_ref::<String>().cloned()),
+    }
+}
+
+/// Panic hook reporting panics as [Decay] errors, rendered just like the regular ones.
+/// Installed with [PanicHook::install] or [install_panic_hook].
+#[derive(Clone, Debug, Default)]
+pub struct PanicHook {
+    format: Option<DecayFormat>,
+    #[cfg(feature = "json")]
+    json: bool,
+}
+
+impl PanicHook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the format to render panics with. Unless set, [DecayFormat::global] is used
+    /// at the moment of every panic.
+    pub fn with_format(mut self, format: DecayFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Turns on or off rendering panics as JSON (the serde representation of [Decay]) instead of text.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Returns the report of the panic, exactly as the installed hook prints it.
+    pub fn render(&self, info: &PanicHookInfo<'_>) -> String {
+        let decay = Decay::<PanicOrigin>::from_panic(info);
+        #[cfg(feature = "json")]
+        if self.json {
+            if let Ok(json) = serde_json::to_string(&decay) {
+                return json;
+            }
+        }
+        match &self.format {
+            Some(format) => decay.display_with(format).to_string(),
+            None => decay.display_global().to_string(),
+        }
+    }
+
+    /// Replaces the current panic hook with this one, which prints reports to the standard error.
+    pub fn install(self) {
+        panic::set_hook(Box::new(move |info| {
+            record_caught(info);
+            eprintln!("{}", self.render(info))
+        }));
+    }
+}
+
+/// Installs the default [PanicHook].
+pub fn install_panic_hook() {
+    PanicHook::new().install()
+}
+
+thread_local! {
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+    static CAUGHT: RefCell<Vec<CodePlace>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Chains the hook recording places of panics inside [catch] before the currently installed one,
+/// which still reports every panic. Done only once per process.
+fn chain_catching_hook() {
+    static CHAINED: Once = Once::new();
+    CHAINED.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            record_caught(info);
+            previous(info)
+        }));
+    });
+}
+
+/// Records the place of the panic if it happens inside [catch]. Until the action of [catch] ends
+/// it is unknown whether the panic escapes it or is caught somewhere inside.
+fn record_caught(info: &PanicHookInfo<'_>) {
+    if CATCHING.with(Cell::get) > 0 {
+        let place = panic_place(info);
+        CAUGHT.with(|places| places.borrow_mut().push(place));
+    }
+}
+
+/// Runs the action, turning its panic (if any) into the internal [Decay] with the panic message
+/// as the note and the panic location as the place. The location is known only if no hook
+/// except [PanicHook] has been installed after the first call of this function, otherwise
+/// the place of this call is used instead.
+///
+/// The first call chains the hook recording panic locations before the installed panic hook,
+/// which keeps reporting every panic (including the one escaping the action) as usual.
+#[track_caller]
+pub fn catch<T, O: StdError, F: FnOnce() -> T + UnwindSafe>(action: F) -> Result<T, Decay<O>> {
+    let caller = Location::caller();
+    chain_catching_hook();
+    let caught_before = CAUGHT.with(|places| places.borrow().len());
+    CATCHING.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(action);
+    CATCHING.with(|depth| depth.set(depth.get() - 1));
+    let mut caught = CAUGHT.with(|places| places.borrow_mut().split_off(caught_before));
+    // The escaping panic is the last one recorded, unless some drop has caught a panic while unwinding.
+    result.map_err(|payload| {
+        let place = caught.pop().unwrap_or_else(|| caller.into());
+        Decay::new(place, payload_note(payload.as_ref()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DecayRoot;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Test error")]
+    struct TestErr;
+
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn caught_panic() {
+        assert_eq!(catch::<_, TestErr, _>(|| 42), Ok(42));
+
+        let line = line!() + 1;
+        let result: Result<u8, Fail> = catch(|| panic!("Plugin {} failed", "loader"));
+        let fail: Fail = result
+            .map_err(rot!("Hosted code crashed"))
+            .expect_err("Panic should be caught");
+        let places = fail
+            .iter()
+            .filter_map(|level| match level {
+                Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => {
+                    Some((note.text(), place.iter().next().map(|place| place.line)))
+                }
+                Decay::External { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            places,
+            vec![
+                (Some("Hosted code crashed"), Some(line + 2)),
+                (Some("Plugin loader failed"), Some(line)),
+            ]
+        );
+
+        let result: Result<(), Fail> = catch(|| std::panic::panic_any(42u8));
+        match result {
+            Err(Decay::Internal { note, .. }) => assert!(note.is_none()),
+            _ => panic!("Result should be Err(Decay::Internal)"),
+        }
+    }
+
+    #[test]
+    fn nested_catch() {
+        let result: Result<Result<(), Fail>, Fail> = catch(|| catch(|| panic!("Inner panic")));
+        match result {
+            Ok(Err(fail)) => assert_eq!(fail.to_string(), "{note: Inner panic}"),
+            _ => panic!("Inner panic should be caught by the inner catch"),
+        }
+    }
+
+    #[test]
+    fn inner_caught_panic() {
+        let result: Result<(), Fail> = catch(|| {
+            assert!(panic::catch_unwind(|| panic!("Handled panic")).is_err());
+        });
+        assert_eq!(result, Ok(()));
+
+        let line = line!() + 3;
+        let result: Result<(), Fail> = catch(|| {
+            assert!(panic::catch_unwind(|| panic!("Handled panic")).is_err());
+            panic!("Escaped panic")
+        });
+        let fail = result.expect_err("Panic should be caught");
+        match fail.root() {
+            DecayRoot::Internal { note, place } => {
+                assert_eq!(note.text(), Some("Escaped panic"));
+                assert_eq!(place.iter().next().map(|place| place.line), Some(line));
+            }
+            DecayRoot::External { .. } => panic!("Expected DecayRoot::Internal"),
+        }
+        assert!(CAUGHT.with(|places| places.borrow().is_empty()));
+    }
+}
@@ -1,25 +1,180 @@
+use crate::ThreadMark;
 use cubob::display_list_from_iter;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use std::{
+    collections::HashSet,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
-    iter::FusedIterator,
+    iter::{FusedIterator, Rev},
+    panic::Location,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    vec::IntoIter,
 };
 
-#[derive(Clone, PartialEq, Eq)]
+/// Static information about the crate some [CodePlace] belongs to.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrateInfo {
+    pub name: &'static str,
+    pub manifest_dir: &'static str,
+}
+
+/// Position in the source code, optionally accompanied by the module, function and crate it belongs to.
+/// Places are serializable but not deserializable: all their strings are `'static`,
+/// so deserialized ones would have to be leaked.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Clone, Eq)]
 pub struct CodePlace {
     pub file: &'static str,
     pub line: u32,
     pub column: u32,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    module: Option<&'static str>,
+    /// Name of the enclosing function relatively to the module (may include `{{closure}}` parts).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    function: Option<&'static str>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    krate: Option<&'static CrateInfo>,
 }
 
 impl CodePlace {
     pub const fn new(file: &'static str, line: u32, column: u32) -> Self {
-        Self { file, line, column }
+        Self {
+            file,
+            line,
+            column,
+            module: None,
+            function: None,
+            krate: None,
+        }
+    }
+
+    pub const fn with_module(mut self, module: Option<&'static str>) -> Self {
+        self.module = module;
+        self
+    }
+
+    pub const fn with_function(mut self, function: Option<&'static str>) -> Self {
+        self.function = function;
+        self
+    }
+
+    pub const fn with_crate(mut self, krate: Option<&'static CrateInfo>) -> Self {
+        self.krate = krate;
+        self
+    }
+
+    pub const fn module(&self) -> Option<&'static str> {
+        self.module
+    }
+
+    pub const fn function(&self) -> Option<&'static str> {
+        self.function
+    }
+
+    pub const fn krate(&self) -> Option<&'static CrateInfo> {
+        self.krate
+    }
+
+    /// Returns the absolute path of the file, if the file path is absolute already
+    /// or the crate information is known. Since `file!()` is relative to the workspace root,
+    /// which is either the crate manifest directory or one of its ancestors, the path is joined
+    /// to the farthest ancestor whose relative path to the manifest directory the file path starts with.
+    /// The file system is not accessed, so the returned path is not guaranteed to exist.
+    pub fn absolute_file(&self) -> Option<PathBuf> {
+        let file = Path::new(self.file);
+        if file.is_absolute() {
+            return Some(file.to_path_buf());
+        }
+        let manifest_dir = Path::new(self.krate?.manifest_dir);
+        manifest_dir
+            .ancestors()
+            .filter(|ancestor| {
+                manifest_dir
+                    .strip_prefix(ancestor)
+                    .is_ok_and(|suffix| file.starts_with(suffix))
+            })
+            .last()
+            .map(|ancestor| ancestor.join(file))
+    }
+}
+
+/// Places are equal when they point to the same position, no matter what metadata
+/// (module, function or crate) is recorded for them.
+impl PartialEq for CodePlace {
+    fn eq(&self, other: &Self) -> bool {
+        self.file == other.file && self.line == other.line && self.column == other.column
+    }
+}
+
+/// Makes the place from the caller location, e.g. the one obtained inside `#[track_caller]` function.
+/// The location knows neither the module nor the function, so they are left unset (as well as the crate).
+impl From<&'static Location<'static>> for CodePlace {
+    fn from(src: &'static Location<'static>) -> Self {
+        Self::new(src.file(), src.line(), src.column())
+    }
+}
+
+/// File path of places whose real file is unknown (or can't be kept, see [static_file]).
+pub(crate) const UNKNOWN_FILE: &str = "<unknown>";
+
+/// Upper bound of memory leaked by [static_file] for all the paths together.
+const STATIC_FILES_BUDGET: usize = 64 * 1024;
+
+/// Returns the file path with the static lifetime, which is needed for [CodePlace] made
+/// from places known at runtime only (like panic locations or places passed through FFI).
+/// Every distinct path is leaked once, until [STATIC_FILES_BUDGET] bytes are leaked in total;
+/// new paths which don't fit into the budget anymore are replaced with [UNKNOWN_FILE].
+pub(crate) fn static_file(file: &str) -> &'static str {
+    static FILES: Mutex<Option<(HashSet<&'static str>, usize)>> = Mutex::new(None);
+    let mut files = match FILES.lock() {
+        Ok(files) => files,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let (files, leaked_len) = files.get_or_insert_with(|| (HashSet::new(), 0));
+    match files.get(file) {
+        Some(known) => known,
+        None if *leaked_len + file.len() > STATIC_FILES_BUDGET => UNKNOWN_FILE,
+        None => {
+            let leaked: &'static str = Box::leak(file.into());
+            files.insert(leaked);
+            *leaked_len += leaked.len();
+            leaked
+        }
     }
 }
 
+#[doc(hidden)]
+pub fn __function_name<F>(_: F, module: &'static str) -> &'static str {
+    let name = std::any::type_name::<F>();
+    let name = name.strip_suffix("::__cadom_function").unwrap_or(name);
+    name.strip_prefix(module)
+        .and_then(|name| name.strip_prefix("::"))
+        .unwrap_or(name)
+}
+
+/// Shows the place as `file:line:column`, or as `module::function (file:line:column)`
+/// when the function is known.
 impl Display for CodePlace {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+        match (self.module, self.function) {
+            (_, None) => write!(f, "{}:{}:{}", self.file, self.line, self.column),
+            (None, Some(function)) => write!(
+                f,
+                "{} ({}:{}:{})",
+                function, self.file, self.line, self.column
+            ),
+            (Some(module), Some(function)) => write!(
+                f,
+                "{}::{} ({}:{}:{})",
+                module, function, self.file, self.line, self.column
+            ),
+        }
     }
 }
 
@@ -29,43 +184,139 @@ impl Debug for CodePlace {
     }
 }
 
+/// Makes [CodePlace] of the macro call. The module path and the crate information are recorded
+/// with `crate-info` feature only; `place!(fn)` records the enclosing function name (see [function!](crate::function!)) too.
 #[macro_export]
 macro_rules! place {
     () => {
         $crate::CodePlace::new(file!(), line!(), column!())
+            .with_module($crate::__place_module!())
+            .with_crate($crate::__place_crate!())
+    };
+
+    (fn) => {
+        $crate::place!().with_function(Some($crate::function!()))
+    };
+}
+
+/// Returns the name of the enclosing function relatively to the current module.
+/// Relies on [std::any::type_name], so the output is the best effort and is not guaranteed to be stable.
+#[macro_export]
+macro_rules! function {
+    () => {{
+        fn __cadom_function() {}
+        $crate::__function_name(__cadom_function, module_path!())
+    }};
+}
+
+#[cfg(feature = "crate-info")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __place_module {
+    () => {
+        Some(module_path!())
+    };
+}
+
+#[cfg(not(feature = "crate-info"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __place_module {
+    () => {
+        None
+    };
+}
+
+#[cfg(feature = "crate-info")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __place_crate {
+    () => {
+        Some(&$crate::CrateInfo {
+            name: env!("CARGO_PKG_NAME"),
+            manifest_dir: env!("CARGO_MANIFEST_DIR"),
+        })
     };
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[cfg(not(feature = "crate-info"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __place_crate {
+    () => {
+        None
+    };
+}
+
+#[derive(Clone)]
 pub struct CodePlaceChain {
     head: CodePlace,
     tail: Option<Box<CodePlaceChain>>,
+    thread: ThreadMark,
 }
 
 impl CodePlaceChain {
-    pub fn prepend(self, place: CodePlace) -> Self {
+    pub fn prepend(mut self, place: CodePlace) -> Self {
         Self {
             head: place,
+            thread: std::mem::take(&mut self.thread),
             tail: Some(Box::new(self)),
         }
     }
 
+    /// Returns the mark of the thread (and task) where the chain was started.
+    /// The mark is empty without `thread-info` feature.
+    pub fn thread(&self) -> &ThreadMark {
+        &self.thread
+    }
+
+    #[cfg(test)]
+    pub(crate) fn unmarked(place: CodePlace) -> Self {
+        Self {
+            head: place,
+            tail: None,
+            thread: ThreadMark::NONE,
+        }
+    }
+
+    /// Iterates over places from the latest added one to the first one.
+    pub fn iter(&self) -> CodePlaceChainIter<'_> {
+        self.into_iter()
+    }
+
+    /// Iterates over places from the first added one to the latest one.
+    pub fn iter_rev(&self) -> Rev<CodePlaceChainIter<'_>> {
+        self.iter().rev()
+    }
+
     pub fn prepend_mut(&mut self, place: CodePlace) -> &mut Self {
         let mut new_node = Self {
             head: place,
             tail: None,
+            thread: ThreadMark::NONE,
         };
         std::mem::swap(self, &mut new_node);
+        std::mem::swap(&mut self.thread, &mut new_node.thread);
         self.tail = Some(Box::new(new_node));
         self
     }
 }
 
+/// Chains are compared by their places only, the thread mark is ignored.
+impl PartialEq for CodePlaceChain {
+    fn eq(&self, other: &Self) -> bool {
+        self.head == other.head && self.tail == other.tail
+    }
+}
+
+impl Eq for CodePlaceChain {}
+
 impl From<CodePlace> for CodePlaceChain {
     fn from(src: CodePlace) -> Self {
         Self {
             head: src,
             tail: None,
+            thread: ThreadMark::current(),
         }
     }
 }
@@ -75,24 +326,55 @@ impl<'a> IntoIterator for &'a CodePlaceChain {
     type IntoIter = CodePlaceChainIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        CodePlaceChainIter(Some(self))
+        CodePlaceChainIter::new(self)
     }
 }
 
-pub struct CodePlaceChainIter<'a>(Option<&'a CodePlaceChain>);
+/// Iterator over places of [CodePlaceChain], going from the latest added place to the first one.
+/// Places are linked in one direction only, so the first step from the back
+/// collects all the remaining places at once.
+pub struct CodePlaceChainIter<'a> {
+    front: Option<&'a CodePlaceChain>,
+    collected: Option<IntoIter<&'a CodePlace>>,
+}
+
+impl<'a> CodePlaceChainIter<'a> {
+    fn new(chain: &'a CodePlaceChain) -> Self {
+        Self {
+            front: Some(chain),
+            collected: None,
+        }
+    }
+}
 
 impl<'a> Iterator for CodePlaceChainIter<'a> {
     type Item = &'a CodePlace;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.0 {
-            None => None,
-            Some(entry) => {
-                let current = &entry.head;
-                self.0 = entry.tail.as_deref();
-                Some(current)
-            }
+        if let Some(collected) = &mut self.collected {
+            return collected.next();
+        }
+        let current = self.front?;
+        self.front = current.tail.as_deref();
+        Some(&current.head)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (&self.collected, self.front) {
+            (Some(collected), _) => collected.size_hint(),
+            (None, Some(_)) => (1, None),
+            (None, None) => (0, Some(0)),
+        }
+    }
+}
+
+impl DoubleEndedIterator for CodePlaceChainIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.collected.is_none() {
+            let remaining = self.by_ref().collect::<Vec<_>>();
+            self.collected = Some(remaining.into_iter());
         }
+        self.collected.as_mut()?.next_back()
     }
 }
 
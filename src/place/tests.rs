@@ -79,3 +79,139 @@ fn chain_prepend_mut() {
         "[src/place/tests.rs:76:23, src/place/tests.rs:71:23, src/place/tests.rs:3:71]"
     );
 }
+
+#[test]
+fn chain_iter_both_directions() {
+    let first = CodePlace::new("src/first.rs", 1, 1);
+    let second = CodePlace::new("src/second.rs", 2, 2);
+    let third = CodePlace::new("src/third.rs", 3, 3);
+    let chain = CodePlaceChain::from(first.clone())
+        .prepend(second.clone())
+        .prepend(third.clone());
+
+    assert_eq!(chain.iter().count(), 3);
+    assert_eq!(
+        chain.iter().collect::<Vec<_>>(),
+        vec![&third, &second, &first]
+    );
+    assert_eq!(
+        chain.iter_rev().collect::<Vec<_>>(),
+        vec![&first, &second, &third]
+    );
+
+    let mut places = chain.iter();
+    assert_eq!(places.next_back(), Some(&first));
+    assert_eq!(places.next(), Some(&third));
+    assert_eq!(places.size_hint(), (1, Some(1)));
+    assert_eq!(places.next_back(), Some(&second));
+    assert_eq!(places.next(), None);
+    assert_eq!(places.next_back(), None);
+}
+
+const TEST_CRATE: CrateInfo = CrateInfo {
+    name: "cadom",
+    manifest_dir: env!("CARGO_MANIFEST_DIR"),
+};
+
+#[test]
+fn absolute_file() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    assert_eq!(TEST_PLACE.absolute_file(), None);
+    assert_eq!(
+        TEST_PLACE
+            .clone()
+            .with_crate(Some(&TEST_CRATE))
+            .absolute_file(),
+        Some(manifest_dir.join("src/place/tests.rs"))
+    );
+    let crate_dir_name = manifest_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("Manifest directory should have a name");
+    let workspace_file: &'static str =
+        Box::leak(format!("{}/src/place/tests.rs", crate_dir_name).into_boxed_str());
+    assert_eq!(
+        CodePlace::new(workspace_file, 1, 1)
+            .with_crate(Some(&TEST_CRATE))
+            .absolute_file(),
+        Some(manifest_dir.join("src/place/tests.rs"))
+    );
+    assert_eq!(
+        CodePlace::new("/some/absolute/path.rs", 1, 1).absolute_file(),
+        Some(PathBuf::from("/some/absolute/path.rs"))
+    );
+}
+
+#[test]
+fn place_equality_ignores_metadata() {
+    assert_eq!(
+        TEST_PLACE_MACRO,
+        CodePlace::new("src/place/tests.rs", 4, 37)
+    );
+    assert_eq!(
+        TEST_PLACE
+            .clone()
+            .with_module(Some("cadom::place::tests"))
+            .with_crate(Some(&TEST_CRATE)),
+        TEST_PLACE
+    );
+    assert_ne!(TEST_PLACE, CodePlace::new("src/place/tests.rs", 3, 72));
+}
+
+#[cfg(feature = "crate-info")]
+#[test]
+fn place_crate_info() {
+    let place = place!();
+    assert_eq!(place.module(), Some("cadom::place::tests"));
+    assert_eq!(place.krate(), Some(&TEST_CRATE));
+}
+
+#[cfg(not(feature = "crate-info"))]
+#[test]
+fn place_no_crate_info() {
+    let place = place!();
+    assert_eq!(place.module(), None);
+    assert_eq!(place.krate(), None);
+}
+
+#[test]
+fn function_name() {
+    assert_eq!(function!(), "function_name");
+    let in_closure = || function!();
+    assert_eq!(in_closure(), "function_name::{{closure}}");
+}
+
+#[test]
+fn place_with_function() {
+    let (place, line) = (place!(fn), line!());
+    assert_eq!(place.function(), Some("place_with_function"));
+    assert_eq!(
+        format!("{}", place.with_module(Some("cadom::place::tests"))),
+        format!(
+            "cadom::place::tests::place_with_function (src/place/tests.rs:{}:26)",
+            line
+        )
+    );
+    assert_eq!(
+        &format!(
+            "{:?}",
+            TEST_PLACE.clone().with_function(Some("some_function"))
+        ),
+        "some_function (src/place/tests.rs:3:71)"
+    );
+}
+
+#[test]
+fn place_from_location() {
+    let (place, line) = (CodePlace::from(std::panic::Location::caller()), line!());
+    assert_eq!(place, CodePlace::new("src/place/tests.rs", line, 42));
+}
+
+#[test]
+fn static_file_budget() {
+    let file = static_file(&String::from("src/runtime.rs"));
+    assert_eq!(file, "src/runtime.rs");
+    assert!(std::ptr::eq(file, static_file("src/runtime.rs")));
+    let huge = "x".repeat(STATIC_FILES_BUDGET + 1);
+    assert_eq!(static_file(&huge), UNKNOWN_FILE);
+}
@@ -0,0 +1,209 @@
+use pyo3::{
+    create_exception,
+    exceptions::PyException,
+    ffi::c_str,
+    prelude::*,
+    sync::PyOnceLock,
+    types::{PyList, PyTraceback},
+};
+use std::error::Error as StdError;
+
+use crate::{CodePlace, CodePlaceChain, Decay};
+
+create_exception!(
+    cadom,
+    DecayError,
+    PyException,
+    "Level of the Rust `Decay` error. Attribute `note` holds the note of the level (or `None`), \
+     attribute `places` holds the list of `(file, line, column, module, function)` tuples. \
+     Inner levels are chained through `__cause__`."
+);
+
+/// Python helpers building tracebacks out of places: every place becomes the frame of the code
+/// compiled with the place file name and line number.
+const HELPERS: &std::ffi::CStr = c_str!(
+    r#"
+import types
+
+
+class _Marker(Exception):
+    pass
+
+
+def synthetic_traceback(places):
+    tb = None
+    for file, line, name in reversed(places):
+        code = compile("\n" * (max(line, 1) - 1) + "raise _Marker", file, "exec")
+        try:
+            exec(code.replace(co_name=name), {"_Marker": _Marker})
+        except _Marker as marker:
+            frame = marker.__traceback__.tb_next
+        tb = types.TracebackType(tb, frame.tb_frame, frame.tb_lasti, frame.tb_lineno)
+    return tb
+"#
+);
+
+fn helpers(py: Python<'_>) -> PyResult<&Bound<'_, PyModule>> {
+    static HELPERS_MODULE: PyOnceLock<Py<PyModule>> = PyOnceLock::new();
+    HELPERS_MODULE
+        .get_or_try_init(py, || {
+            PyModule::from_code(
+                py,
+                HELPERS,
+                c_str!("cadom_helpers.py"),
+                c_str!("cadom_helpers"),
+            )
+            .map(Bound::unbind)
+        })
+        .map(|module| module.bind(py))
+}
+
+fn frame_name(place: &CodePlace) -> &'static str {
+    place.function().or(place.module()).unwrap_or("<unknown>")
+}
+
+fn synthetic_traceback<'py>(
+    py: Python<'py>,
+    chain: &CodePlaceChain,
+) -> PyResult<Bound<'py, PyTraceback>> {
+    let places = chain
+        .iter()
+        .map(|place| (place.file, place.line, frame_name(place)))
+        .collect::<Vec<_>>();
+    Ok(helpers(py)?
+        .getattr("synthetic_traceback")?
+        .call1((places,))?
+        .cast_into::<PyTraceback>()?)
+}
+
+fn level_err<O: StdError>(py: Python<'_>, level: &Decay<O>) -> PyErr {
+    let (err, note, chain) = match level {
+        Decay::External { error } => (DecayError::new_err(error.to_string()), None, None),
+        Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => (
+            DecayError::new_err(note.text().unwrap_or_default().to_owned()),
+            note.text(),
+            Some(place),
+        ),
+    };
+    // The exception is still usable without attributes, so failures to set them are ignored.
+    let _ = describe_level(py, &err, note, chain);
+    err
+}
+
+fn describe_level(
+    py: Python<'_>,
+    err: &PyErr,
+    note: Option<&str>,
+    chain: Option<&CodePlaceChain>,
+) -> PyResult<()> {
+    let value = err.value(py);
+    value.setattr("note", note)?;
+    let places = chain
+        .into_iter()
+        .flatten()
+        .map(|place| {
+            (
+                place.file,
+                place.line,
+                place.column,
+                place.module(),
+                place.function(),
+            )
+        })
+        .collect::<Vec<_>>();
+    value.setattr("places", PyList::new(py, places)?)?;
+    if let Some(chain) = chain {
+        err.set_traceback(py, Some(synthetic_traceback(py, chain)?));
+    }
+    Ok(())
+}
+
+impl<O: StdError> Decay<O> {
+    /// Converts the decay into [DecayError] of the outermost level, whose `__cause__` chain
+    /// mirrors the inner levels. Places of every level are also shown as traceback entries.
+    pub fn to_py_err(&self, py: Python<'_>) -> PyErr {
+        let mut cause: Option<PyErr> = None;
+        for level in self.iter_rev() {
+            let err = level_err(py, level);
+            err.set_cause(py, cause.take());
+            cause = Some(err);
+        }
+        cause.expect("Decay always has at least one level")
+    }
+}
+
+/// Makes the Python exception chain (see [Decay::to_py_err]), so `?` works in `#[pyfunction]`s.
+impl<O: StdError> From<Decay<O>> for PyErr {
+    fn from(src: Decay<O>) -> Self {
+        Python::attach(|py| src.to_py_err(py))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection reset")]
+    struct TestErr;
+
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn exception_chain() {
+        let fail = Fail::from(TestErr)
+            .further(
+                CodePlace::new("src/net.rs", 12, 5)
+                    .with_module(Some("app::net"))
+                    .with_function(Some("read")),
+                "Can't read the response",
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(CodePlace::new("src/main.rs", 7, 1), "Request failed");
+
+        Python::initialize();
+        Python::attach(|py| {
+            let err = PyErr::from(fail);
+            let locals = PyDict::new(py);
+            locals
+                .set_item("error", err.value(py))
+                .expect("Item should be set");
+            py.run(
+                c_str!(
+                    r#"
+import traceback
+
+outer = error
+middle = outer.__cause__
+root = middle.__cause__
+assert type(outer).__name__ == "DecayError"
+assert str(outer) == "Request failed" and outer.note == "Request failed"
+assert outer.places == [("src/main.rs", 7, 1, None, None)]
+assert middle.note == "Can't read the response"
+assert middle.places == [
+    ("src/client.rs", 40, 9, None, None),
+    ("src/net.rs", 12, 5, "app::net", "read"),
+]
+assert str(root) == "Connection reset" and root.note is None and root.places == []
+assert root.__cause__ is None
+
+frames = traceback.extract_tb(middle.__traceback__)
+assert [(frame.filename, frame.lineno, frame.name) for frame in frames] == [
+    ("src/client.rs", 40, "<unknown>"),
+    ("src/net.rs", 12, "read"),
+], frames
+report = "".join(traceback.format_exception(outer))
+assert 'File "src/net.rs", line 12, in read' in report, report
+assert "The above exception was the direct cause" in report, report
+"#
+                ),
+                None,
+                Some(&locals),
+            )
+            .map_err(|err| err.to_string())
+            .expect("Python assertions should pass");
+        });
+    }
+}
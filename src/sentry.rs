@@ -0,0 +1,181 @@
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{any::type_name, error::Error as StdError};
+
+use crate::{CodePlace, Decay};
+
+/// Sentry event payload made from [Decay], ready to be sent as JSON to the store endpoint
+/// (or wrapped into an envelope). Every level becomes the exception value (the root one goes first,
+/// as Sentry expects for chained exceptions), places of the level become its stacktrace frames,
+/// and the fingerprint is [Decay::fingerprint] (as 16 hex digits), so decays failing the same way
+/// are grouped together, whatever their notes and origin messages say.
+/// Fields like `event_id` and `timestamp` are left for the sender to fill.
+#[cfg_attr(docsrs, doc(cfg(feature = "sentry")))]
+pub struct SentryEvent<'a, O: StdError>(&'a Decay<O>);
+
+impl<O: StdError> Decay<O> {
+    /// Returns the Sentry event payload describing the decay.
+    #[cfg_attr(docsrs, doc(cfg(feature = "sentry")))]
+    pub fn sentry_event(&self) -> SentryEvent<'_, O> {
+        SentryEvent(self)
+    }
+}
+
+#[derive(Serialize)]
+struct ExceptionValues<'a> {
+    values: Vec<Exception<'a>>,
+}
+
+#[derive(Serialize)]
+struct Exception<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stacktrace: Option<Stacktrace<'a>>,
+}
+
+#[derive(Serialize)]
+struct Stacktrace<'a> {
+    frames: Vec<Frame<'a>>,
+}
+
+#[derive(Serialize)]
+struct Frame<'a> {
+    filename: &'a str,
+    lineno: u32,
+    colno: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    module: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<&'a str>,
+}
+
+impl<'a> From<&'a CodePlace> for Frame<'a> {
+    fn from(place: &'a CodePlace) -> Self {
+        Self {
+            filename: place.file,
+            lineno: place.line,
+            colno: place.column,
+            module: place.module(),
+            function: place.function(),
+        }
+    }
+}
+
+fn exception_of<O: StdError>(level: &Decay<O>) -> Exception<'_> {
+    match level {
+        Decay::External { error } => Exception {
+            kind: type_name::<O>(),
+            value: Some(error.to_string()),
+            stacktrace: None,
+        },
+        Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => Exception {
+            kind: "cadom::Decay",
+            value: note.text().map(str::to_owned),
+            stacktrace: Some(Stacktrace {
+                frames: place.iter().map(Frame::from).collect(),
+            }),
+        },
+    }
+}
+
+impl<O: StdError> Serialize for SentryEvent<'_, O> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let exception = ExceptionValues {
+            values: self.0.iter_rev().map(exception_of).collect(),
+        };
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("platform", "native")?;
+        map.serialize_entry("level", "error")?;
+        map.serialize_entry("exception", &exception)?;
+        map.serialize_entry("fingerprint", &[format!("{:016x}", self.0.fingerprint())])?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection reset")]
+    struct TestErr;
+
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn event_payload() {
+        let fail = Fail::from(TestErr)
+            .further(
+                CodePlace::new("src/net.rs", 12, 5)
+                    .with_module(Some("app::net"))
+                    .with_function(Some("read")),
+                "Can't read the response",
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(CodePlace::new("src/main.rs", 7, 1), "Request failed");
+        let event = serde_json::to_value(fail.sentry_event()).expect("Event should serialize");
+        assert_eq!(
+            event,
+            json!({
+                "platform": "native",
+                "level": "error",
+                "exception": {
+                    "values": [
+                        {
+                            "type": type_name::<TestErr>(),
+                            "value": "Connection reset",
+                        },
+                        {
+                            "type": "cadom::Decay",
+                            "value": "Can't read the response",
+                            "stacktrace": {
+                                "frames": [
+                                    {"filename": "src/client.rs", "lineno": 40, "colno": 9},
+                                    {
+                                        "filename": "src/net.rs",
+                                        "lineno": 12,
+                                        "colno": 5,
+                                        "module": "app::net",
+                                        "function": "read",
+                                    },
+                                ],
+                            },
+                        },
+                        {
+                            "type": "cadom::Decay",
+                            "value": "Request failed",
+                            "stacktrace": {
+                                "frames": [{"filename": "src/main.rs", "lineno": 7, "colno": 1}],
+                            },
+                        },
+                    ],
+                },
+                "fingerprint": [format!("{:016x}", fail.fingerprint())],
+            })
+        );
+
+        let internal = Fail::new_unnoted(CodePlace::new("src/config.rs", 3, 14));
+        let event = serde_json::to_value(internal.sentry_event()).expect("Event should serialize");
+        assert_eq!(
+            event["fingerprint"],
+            json!([format!("{:016x}", internal.fingerprint())])
+        );
+        let other = Fail::new_unnoted(CodePlace::new("src/config.rs", 5, 14));
+        let other = serde_json::to_value(other.sentry_event()).expect("Event should serialize");
+        assert_ne!(other["fingerprint"], event["fingerprint"]);
+        assert_eq!(
+            event["exception"]["values"],
+            json!([{
+                "type": "cadom::Decay",
+                "stacktrace": {"frames": [{"filename": "src/config.rs", "lineno": 3, "colno": 14}]},
+            }])
+        );
+    }
+}
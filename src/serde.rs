@@ -1,14 +1,17 @@
 #[cfg(feature = "schema")]
+use crate::CodePlace;
+#[cfg(feature = "schema")]
 use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{Deserialize, Serialize, Serializer};
 use std::{
     borrow::{Borrow, BorrowMut},
+    collections::BTreeMap,
     error::Error as StdError,
     fmt::{Display, Formatter, Result as FmtResult},
     ops::{Deref, DerefMut},
 };
 
-use crate::Decay;
+use crate::{CodePlaceChain, Decay, DecayOrder, Note, NoteArgs};
 
 /// Serialization implementation for [Decay] through serde.
 /// Since some information is assumed redundant for inter-service communication,
@@ -21,21 +24,111 @@ impl<O: StdError + Serialize> Serialize for Decay<O> {
     where
         S: Serializer,
     {
-        use serde::ser::SerializeSeq;
-
-        let mut seq = serializer.serialize_seq(None)?;
-        for decay in self {
-            match decay {
-                Decay::External { error } => seq.serialize_element(error)?,
-                Decay::Internal { note, .. } | Decay::Further { note, .. } => {
-                    if let Some(text) = note.text() {
-                        seq.serialize_element(text)?
+        serialize_levels(self.iter(), false, serializer)
+    }
+}
+
+impl<O: StdError + Serialize> Decay<O> {
+    /// Serializes the same way [Serialize] implementation does, but with levels going in the given order.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn serialize_ordered<S>(&self, order: DecayOrder, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match order {
+            DecayOrder::OutermostFirst => serialize_levels(self.iter(), false, serializer),
+            DecayOrder::InnermostFirst => serialize_levels(self.iter_rev(), false, serializer),
+        }
+    }
+
+    /// Serializes the same way [Serialize] implementation does, but notes made from format strings
+    /// (see [Note::template]) go as `{"text": ..., "template": ..., "args": {name: value, ...}}` maps.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn serialize_templated<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_levels(self.iter(), true, serializer)
+    }
+}
+
+/// Serializes [Decay] with levels going from the root one to the outermost one.
+/// Intended to be used through `#[serde(serialize_with = "cadom::serialize_innermost_first")]`.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub fn serialize_innermost_first<O, S>(decay: &Decay<O>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    O: StdError + Serialize,
+    S: Serializer,
+{
+    decay.serialize_ordered(DecayOrder::InnermostFirst, serializer)
+}
+
+/// Serializes [Decay] keeping format templates and arguments of notes (see [Decay::serialize_templated]).
+/// Intended to be used through `#[serde(serialize_with = "cadom::serialize_templated")]`.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub fn serialize_templated<O, S>(decay: &Decay<O>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    O: StdError + Serialize,
+    S: Serializer,
+{
+    decay.serialize_templated(serializer)
+}
+
+struct TemplatedNote<'a>(&'a Note);
+
+/// Serializes [NoteArgs] as a map from names to text representations.
+impl Serialize for NoteArgs {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl Serialize for TemplatedNote<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("text", &self.0.text())?;
+        map.serialize_entry("template", &self.0.template())?;
+        map.serialize_entry("args", self.0.args())?;
+        map.end()
+    }
+}
+
+fn serialize_levels<'a, O, I, S>(
+    levels: I,
+    templated: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    O: StdError + Serialize + 'a,
+    I: Iterator<Item = &'a Decay<O>>,
+    S: Serializer,
+{
+    use serde::ser::SerializeSeq;
+
+    let mut seq = serializer.serialize_seq(None)?;
+    for decay in levels {
+        match decay {
+            Decay::External { error } => seq.serialize_element(error)?,
+            Decay::Internal { note, .. } | Decay::Further { note, .. } => {
+                match (note.text(), note.template()) {
+                    (None, _) => {}
+                    (Some(_), Some(_)) if templated => {
+                        seq.serialize_element(&TemplatedNote(note))?
                     }
+                    (Some(text), _) => seq.serialize_element(text)?,
                 }
             }
         }
-        seq.end()
     }
+    seq.end()
 }
 
 #[cfg(feature = "schema")]
@@ -47,7 +140,7 @@ impl<O: StdError + Serialize + JsonSchema> JsonSchema for Decay<O> {
         true
     }
 
-    /// Will return name like "Decay(<smth>)", where <smth> is the type O schema name.
+    /// Will return name like `Decay(<smth>)`, where `<smth>` is the type O schema name.
     /// Avoids redundant memory allocation by writing into the same [String],
     /// but can't avoid copying bytes of the type O schema name (shifts them).
     fn schema_name() -> String {
@@ -65,13 +158,45 @@ impl<O: StdError + Serialize + JsonSchema> JsonSchema for Decay<O> {
     }
 }
 
+/// Serializes [CodePlaceChain] as a sequence of places, from the latest added one to the first one.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl Serialize for CodePlaceChain {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self)
+    }
+}
+
+#[cfg(feature = "schema")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "serde", feature = "schema"))))]
+impl JsonSchema for CodePlaceChain {
+    fn schema_name() -> String {
+        "CodePlaceChain".to_owned()
+    }
+
+    /// Since [CodePlaceChain] serializes as a sequence of places, it shares the schema with [Vec] of them.
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        <Vec<CodePlace> as JsonSchema>::json_schema(gen)
+    }
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum DecayDeserItem<O: StdError> {
     Internal(String),
     External(O),
+    /// Note serialized along with its format template and arguments (see [Decay::serialize_templated]).
+    /// Tried after [DecayDeserItem::External], so origins deserializable from such maps take them.
+    Templated {
+        text: String,
+        template: String,
+        args: BTreeMap<String, String>,
+    },
 }
 
 impl<O: StdError + Display> Display for DecayDeserItem<O> {
@@ -79,6 +204,7 @@ impl<O: StdError + Display> Display for DecayDeserItem<O> {
         match self {
             DecayDeserItem::Internal(x) => f.write_str(x),
             DecayDeserItem::External(o) => Display::fmt(o, f),
+            DecayDeserItem::Templated { text, .. } => f.write_str(text),
         }
     }
 }
@@ -222,6 +348,84 @@ mod tests {
         assert_eq!(actual_text, EXAMPLE_TEXT_INTERNAL);
     }
 
+    #[test]
+    fn serialize_decay_innermost_first() {
+        #[derive(Serialize)]
+        struct Wrapper {
+            #[serde(serialize_with = "crate::serialize_innermost_first")]
+            fail: TestFail,
+        }
+
+        let start: TestFail = decay!("Some {}-level note (formatted) about error", 0);
+        let fail: TestFail = rot!()(start);
+        let fail: TestFail = rot!("Some note #{}, formatted", 2)(fail);
+
+        let actual_text = serde_json::to_string(&Wrapper { fail })
+            .expect("Serialization should complete successfully");
+
+        assert_eq!(
+            actual_text,
+            r#"{"fail":["Some 0-level note (formatted) about error","Some note #2, formatted"]}"#
+        );
+    }
+
+    #[test]
+    fn serialize_place_chain() {
+        let chain = CodePlaceChain::from(crate::CodePlace::new("src/lib.rs", 1, 2)).prepend(
+            crate::CodePlace::new("src/main.rs", 3, 4)
+                .with_module(Some("app"))
+                .with_function(Some("main")),
+        );
+
+        let actual_text =
+            serde_json::to_string(&chain).expect("Serialization should complete successfully");
+
+        assert_eq!(
+            actual_text,
+            r#"[{"file":"src/main.rs","line":3,"column":4,"module":"app","function":"main"},{"file":"src/lib.rs","line":1,"column":2}]"#
+        );
+    }
+
+    #[test]
+    fn serialize_decay_templated() {
+        #[derive(Serialize)]
+        struct Wrapper {
+            #[serde(serialize_with = "crate::serialize_templated")]
+            fail: TestFail,
+        }
+
+        let level = 0;
+        let start: TestFail = decay!("Some {}-level note about {topic}", level, topic = "error");
+        let fail: TestFail = rot!("Static note")(start);
+
+        let actual_text = serde_json::to_string(&Wrapper { fail })
+            .expect("Serialization should complete successfully");
+        #[cfg(feature = "macros")]
+        let args = [("0", "0"), ("topic", "error")];
+        #[cfg(not(feature = "macros"))]
+        let args: [(&str, &str); 0] = [];
+        let expected_text = format!(
+            r#"{{"fail":["Static note",{{"text":"Some 0-level note about error","template":"Some {{}}-level note about {{topic}}","args":{}}}]}}"#,
+            serde_json::to_string(&args.iter().copied().collect::<BTreeMap<_, _>>()).unwrap()
+        );
+        assert_eq!(actual_text, expected_text);
+
+        let decay_deser: TestDeser = serde_json::from_str(&actual_text[8..actual_text.len() - 1])
+            .expect("Deserialization should complete successfully");
+        assert_eq!(
+            decay_deser[1],
+            DecayDeserItem::Templated {
+                text: "Some 0-level note about error".into(),
+                template: "Some {}-level note about {topic}".into(),
+                args: args
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            }
+        );
+        assert_eq!(decay_deser[1].to_string(), "Some 0-level note about error");
+    }
+
     #[test]
     fn deserialize_decay_deser_external() {
         let decay_deser: TestDeser = serde_json::from_str(EXAMPLE_TEXT_EXTERNAL)
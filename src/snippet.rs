@@ -0,0 +1,164 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult, Write},
+    fs::File,
+    io::{BufRead, BufReader, Lines},
+    path::Path,
+};
+
+use crate::{CodePlace, Decay};
+
+/// Report of [Decay] which shows the source line for every [CodePlace] (like rustc diagnostics do).
+/// File paths of places are resolved relatively to the given root directory; places whose
+/// source can't be read are shown as bare locations.
+pub struct DecaySnippets<'a, O: StdError> {
+    decay: &'a Decay<O>,
+    root: Cow<'a, Path>,
+}
+
+/// Lines of one source file, read only as far as the places need them.
+struct SourceLines {
+    reader: Option<Lines<BufReader<File>>>,
+    read: Vec<String>,
+}
+
+impl SourceLines {
+    fn open(path: &Path) -> Self {
+        Self {
+            reader: File::open(path)
+                .ok()
+                .map(|file| BufReader::new(file).lines()),
+            read: Vec::new(),
+        }
+    }
+
+    fn line(&mut self, number: u32) -> Option<&str> {
+        let index = (number as usize).checked_sub(1)?;
+        while self.read.len() <= index {
+            match self.reader.as_mut()?.next() {
+                Some(Ok(line)) => self.read.push(line),
+                _ => {
+                    self.reader = None;
+                    return None;
+                }
+            }
+        }
+        Some(&self.read[index])
+    }
+}
+
+impl<'a, O: StdError> DecaySnippets<'a, O> {
+    pub fn new<R: Into<Cow<'a, Path>>>(decay: &'a Decay<O>, root: R) -> Self {
+        Self {
+            decay,
+            root: root.into(),
+        }
+    }
+
+    fn write_place(
+        &self,
+        f: &mut Formatter<'_>,
+        sources: &mut HashMap<&'static str, SourceLines>,
+        place: &CodePlace,
+    ) -> FmtResult {
+        writeln!(f, "  --> {}", place)?;
+        let line = match sources
+            .entry(place.file)
+            .or_insert_with(|| SourceLines::open(&self.root.join(place.file)))
+            .line(place.line)
+        {
+            Some(line) => line,
+            None => return Ok(()),
+        };
+        let gutter = place.line.to_string().len();
+        writeln!(f, "{:gutter$} |", "", gutter = gutter)?;
+        writeln!(f, "{} | {}", place.line, line)?;
+        write!(f, "{:gutter$} | ", "", gutter = gutter)?;
+        for c in line.chars().take((place.column as usize).saturating_sub(1)) {
+            f.write_char(if c == '\t' { '\t' } else { ' ' })?;
+        }
+        writeln!(f, "^")
+    }
+}
+
+impl<O: StdError> Display for DecaySnippets<'_, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut sources = HashMap::new();
+        for level in self.decay {
+            match level {
+                Decay::External { error } => writeln!(f, "error: {}", error)?,
+                Decay::Internal { note, place } | Decay::Further { note, place, .. } => {
+                    if let Some(text) = note.text() {
+                        writeln!(f, "note: {}", text)?;
+                    }
+                    for place in place {
+                        self.write_place(f, &mut sources, place)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<O: StdError> Decay<O> {
+    /// Returns the report with source snippets, reading files relatively to the given root
+    /// (which is usually `env!("CARGO_MANIFEST_DIR")` or the workspace root).
+    pub fn snippets<'a, R: Into<Cow<'a, Path>>>(&'a self, root: R) -> DecaySnippets<'a, O> {
+        DecaySnippets::new(self, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("{0}")]
+    struct TestErr(&'static str);
+
+    type Fail = Decay<TestErr>;
+
+    const ROOT: &str = env!("CARGO_MANIFEST_DIR");
+
+    #[test]
+    fn existing_source() {
+        let (fail, line): (Fail, u32) = (decay!("Root note"), line!());
+        let report = fail.snippets(Path::new(ROOT)).to_string();
+        let expected = format!(
+            "note: Root note\n  --> src/snippet.rs:{line}:42\n{pad} |\n{line} |         let (fail, line): (Fail, u32) = (decay!(\"Root note\"), line!());\n{pad} |                                          ^\n",
+            line = line,
+            pad = " ".repeat(line.to_string().len()),
+        );
+        assert_eq!(report, expected);
+    }
+
+    #[test]
+    fn missing_source() {
+        let fail = Fail::from(TestErr("External error"))
+            .further(CodePlace::new("src/missing.rs", 1, 1), "Outer note")
+            .further_unnoted(CodePlace::new("src/snippet.rs", 100_000, 1));
+        assert_eq!(
+            fail.snippets(Path::new(ROOT)).to_string(),
+            "note: Outer note\n  --> src/snippet.rs:100000:1\n  --> src/missing.rs:1:1\nerror: External error\n"
+        );
+    }
+
+    #[test]
+    fn tabulated_source() {
+        let root = tempfile::tempdir().expect("Temporary directory should be created");
+        std::fs::write(
+            root.path().join("tabs.rs"),
+            "fn main() {\n\t\tfail()?;\n}\n",
+        )
+        .expect("Temporary file should be written");
+        let fail = Fail::new(CodePlace::new("tabs.rs", 2, 9), "Tabulated")
+            .further_unnoted(CodePlace::new("tabs.rs", 1, 4));
+        assert_eq!(
+            fail.snippets(root.path()).to_string(),
+            "note: Tabulated\n  --> tabs.rs:1:4\n  |\n1 | fn main() {\n  |    ^\n  --> tabs.rs:2:9\n  |\n2 | \t\tfail()?;\n  | \t\t      ^\n"
+        );
+    }
+}
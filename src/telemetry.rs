@@ -0,0 +1,173 @@
+use opentelemetry::{
+    trace::{get_active_span, Span},
+    KeyValue,
+};
+use std::{
+    error::Error as StdError,
+    fmt::{Result as FmtResult, Write},
+};
+
+use crate::Decay;
+
+/// Name of the span event made from [Decay], as the semantic conventions prescribe.
+const EXCEPTION_EVENT: &str = "exception";
+
+impl<O: StdError> Decay<O> {
+    /// Returns attributes describing the decay according to the OpenTelemetry semantic conventions:
+    /// - `exception.type` is the origin type name for the external decay and `cadom::Decay` otherwise;
+    /// - `exception.message` is the outermost note (or the origin message if there are no notes);
+    /// - `exception.stacktrace` lists notes and places of all levels, from the outermost one.
+    #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+    pub fn exception_attributes(&self) -> Vec<KeyValue> {
+        let mut stacktrace = String::new();
+        // Writing into the string can't fail.
+        let _ = self.write_stacktrace(&mut stacktrace);
+        vec![
+            KeyValue::new("exception.type", self.root_type_name()),
+            KeyValue::new("exception.message", self.headline()),
+            KeyValue::new("exception.stacktrace", stacktrace),
+        ]
+    }
+
+    /// Adds the `exception` event (see [Decay::exception_attributes]) to the span.
+    /// The span status is left untouched, just like [Span::record_error] does.
+    #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+    pub fn record_on<S: Span>(&self, span: &mut S) {
+        if span.is_recording() {
+            span.add_event(EXCEPTION_EVENT, self.exception_attributes());
+        }
+    }
+
+    /// Adds the `exception` event to the currently active span (see [Decay::record_on]).
+    #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+    pub fn record_on_current_span(&self) {
+        get_active_span(|span| {
+            if span.is_recording() {
+                span.add_event(EXCEPTION_EVENT, self.exception_attributes());
+            }
+        })
+    }
+
+    fn write_stacktrace(&self, stacktrace: &mut String) -> FmtResult {
+        for (index, level) in self.iter().enumerate() {
+            let (message, chain) = match level {
+                Decay::External { error } => (Some(error.to_string()), None),
+                Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => {
+                    (note.text().map(str::to_owned), Some(place))
+                }
+            };
+            if index > 0 {
+                stacktrace.push_str("\nCaused by:");
+            }
+            if let Some(message) = message {
+                if index > 0 {
+                    stacktrace.push(' ');
+                }
+                stacktrace.push_str(&message);
+            }
+            for place in chain.into_iter().flatten() {
+                if !stacktrace.is_empty() {
+                    stacktrace.push('\n');
+                }
+                write!(stacktrace, "    at {}", place)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{
+        trace::{TraceContextExt, Tracer, TracerProvider},
+        Context, Value,
+    };
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+
+    use std::any::type_name;
+
+    use super::*;
+    use crate::CodePlace;
+
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection reset")]
+    struct TestErr;
+
+    type Fail = Decay<TestErr>;
+
+    fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a Value> {
+        attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == key)
+            .map(|attribute| &attribute.value)
+    }
+
+    #[test]
+    fn span_exception_events() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer("cadom");
+
+        let external = Fail::from(TestErr)
+            .further(
+                CodePlace::new("src/net.rs", 12, 5)
+                    .with_module(Some("app::net"))
+                    .with_function(Some("read")),
+                "Can't read the response",
+            )
+            .further_unnoted(CodePlace::new("src/client.rs", 40, 9))
+            .further(CodePlace::new("src/main.rs", 7, 1), "Request failed");
+        let internal = Fail::new_unnoted(CodePlace::new("src/config.rs", 3, 14));
+
+        let mut span = tracer.start("explicit");
+        external.record_on(&mut span);
+        span.end();
+        let span = tracer.start("current");
+        let _guard = Context::current_with_span(span).attach();
+        internal.record_on_current_span();
+        get_active_span(|span| span.end());
+
+        let spans = exporter
+            .get_finished_spans()
+            .expect("Spans should be exported");
+        assert_eq!(spans.len(), 2);
+
+        let events = &spans[0].events.events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "exception");
+        let attributes = &events[0].attributes;
+        assert_eq!(
+            attribute(attributes, "exception.type"),
+            Some(&Value::from(type_name::<TestErr>()))
+        );
+        assert_eq!(
+            attribute(attributes, "exception.message"),
+            Some(&Value::from("Request failed"))
+        );
+        assert_eq!(
+            attribute(attributes, "exception.stacktrace"),
+            Some(&Value::from(
+                "Request failed\n    at src/main.rs:7:1\n\
+                 Caused by: Can't read the response\n    at src/client.rs:40:9\n    \
+                 at app::net::read (src/net.rs:12:5)\n\
+                 Caused by: Connection reset"
+            ))
+        );
+
+        let attributes = &spans[1].events.events[0].attributes;
+        assert_eq!(
+            attribute(attributes, "exception.type"),
+            Some(&Value::from("cadom::Decay"))
+        );
+        assert_eq!(
+            attribute(attributes, "exception.message"),
+            Some(&Value::from(""))
+        );
+        assert_eq!(
+            attribute(attributes, "exception.stacktrace"),
+            Some(&Value::from("    at src/config.rs:3:14"))
+        );
+    }
+}
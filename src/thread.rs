@@ -0,0 +1,194 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+#[cfg(feature = "thread-info")]
+use std::{
+    cell::RefCell,
+    sync::Arc,
+    thread::{self, Thread, ThreadId},
+};
+
+/// Identity of the thread (and of the tokio task, if any) where the root level of [Decay](crate::Decay)
+/// was created (for the external root, where it was first wrapped: see [Decay::thread](crate::Decay::thread)).
+/// It is captured with the `thread-info` feature only (tokio task id requires
+/// the `tokio-task` feature as well), otherwise the mark is always empty.
+/// Marks are equal when they point to the same thread and task; they never affect comparison of errors.
+#[derive(Clone, Default)]
+pub struct ThreadMark {
+    // Kept behind a single pointer (shared by marks captured in the same thread and task),
+    // so the mark doesn't grow every place chain by more than a word.
+    #[cfg(feature = "thread-info")]
+    marked: Option<Arc<Marked>>,
+}
+
+#[cfg(feature = "thread-info")]
+struct Marked {
+    thread: Thread,
+    #[cfg(feature = "tokio-task")]
+    task: Option<tokio::task::Id>,
+}
+
+#[cfg(feature = "thread-info")]
+impl Marked {
+    fn current() -> Self {
+        Self {
+            thread: thread::current(),
+            #[cfg(feature = "tokio-task")]
+            task: tokio::task::try_id(),
+        }
+    }
+
+    #[cfg(feature = "tokio-task")]
+    fn is_current(&self) -> bool {
+        self.task == tokio::task::try_id()
+    }
+
+    #[cfg(not(feature = "tokio-task"))]
+    fn is_current(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "thread-info")]
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Marked>>> = const { RefCell::new(None) };
+}
+
+impl ThreadMark {
+    pub const NONE: Self = Self {
+        #[cfg(feature = "thread-info")]
+        marked: None,
+    };
+
+    /// Captures the identity of the current thread and task (as far as the enabled features allow).
+    #[cfg(feature = "thread-info")]
+    pub fn current() -> Self {
+        let marked = CURRENT
+            .try_with(|current| {
+                let mut current = current.borrow_mut();
+                match &*current {
+                    Some(marked) if marked.is_current() => marked.clone(),
+                    _ => current.insert(Arc::new(Marked::current())).clone(),
+                }
+            })
+            .unwrap_or_else(|_| Arc::new(Marked::current()));
+        Self {
+            marked: Some(marked),
+        }
+    }
+
+    /// Captures the identity of the current thread and task (as far as the enabled features allow).
+    #[cfg(not(feature = "thread-info"))]
+    pub fn current() -> Self {
+        Self::NONE
+    }
+
+    #[cfg(feature = "thread-info")]
+    pub fn is_empty(&self) -> bool {
+        self.marked.is_none()
+    }
+
+    #[cfg(not(feature = "thread-info"))]
+    pub fn is_empty(&self) -> bool {
+        true
+    }
+
+    #[cfg(feature = "thread-info")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "thread-info")))]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.marked.as_ref()?.thread.name()
+    }
+
+    #[cfg(feature = "thread-info")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "thread-info")))]
+    pub fn thread_id(&self) -> Option<ThreadId> {
+        self.marked.as_ref().map(|marked| marked.thread.id())
+    }
+
+    #[cfg(feature = "tokio-task")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio-task")))]
+    pub fn task_id(&self) -> Option<tokio::task::Id> {
+        self.marked.as_ref()?.task
+    }
+}
+
+impl PartialEq for ThreadMark {
+    fn eq(&self, _other: &Self) -> bool {
+        #[cfg(feature = "thread-info")]
+        if self.thread_id() != _other.thread_id() {
+            return false;
+        }
+        #[cfg(feature = "tokio-task")]
+        if self.task_id() != _other.task_id() {
+            return false;
+        }
+        true
+    }
+}
+
+impl Eq for ThreadMark {}
+
+/// Shows the mark as `name (ThreadId(N))`, followed by `, task M` when the task is known;
+/// the empty mark is shown as nothing.
+impl Display for ThreadMark {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        #[cfg(not(feature = "thread-info"))]
+        let _ = f;
+        #[cfg(feature = "thread-info")]
+        if let Some(marked) = &self.marked {
+            match marked.thread.name() {
+                Some(name) => write!(f, "{} ({:?})", name, marked.thread.id())?,
+                None => write!(f, "{:?}", marked.thread.id())?,
+            }
+        }
+        #[cfg(feature = "tokio-task")]
+        if let Some(task) = self.task_id() {
+            write!(f, ", task {}", task)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ThreadMark {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(all(test, feature = "thread-info"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_thread() {
+        let mark = thread::Builder::new()
+            .name("cadom-worker".into())
+            .spawn(ThreadMark::current)
+            .expect("Thread should be spawned")
+            .join()
+            .expect("Thread should not panic");
+        assert!(!mark.is_empty());
+        assert_eq!(mark.thread_name(), Some("cadom-worker"));
+        let id = mark.thread_id().expect("Thread id should be captured");
+        assert_ne!(Some(id), ThreadMark::current().thread_id());
+        assert_eq!(mark.to_string(), format!("cadom-worker ({:?})", id));
+        assert_ne!(mark, ThreadMark::NONE);
+        assert_eq!(mark, mark.clone());
+        assert!(ThreadMark::NONE.is_empty());
+        assert_eq!(ThreadMark::NONE.to_string(), "");
+    }
+
+    #[cfg(feature = "tokio-task")]
+    #[test]
+    fn tokio_task() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Runtime should be built");
+        let (mark, task) = runtime.block_on(async {
+            tokio::spawn(async { (ThreadMark::current(), tokio::task::id()) })
+                .await
+                .expect("Task should not panic")
+        });
+        assert_eq!(mark.task_id(), Some(task));
+        assert!(mark.to_string().ends_with(&format!(", task {}", task)));
+        assert_eq!(ThreadMark::current().task_id(), None);
+    }
+}
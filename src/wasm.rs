@@ -0,0 +1,129 @@
+use js_sys::{Error as JsError, Reflect};
+use std::{
+    error::Error as StdError,
+    fmt::{Result as FmtResult, Write},
+};
+use wasm_bindgen::JsValue;
+
+use crate::{CodePlaceChain, Decay};
+
+/// Name given to JavaScript errors made from [Decay] levels.
+pub const JS_ERROR_NAME: &str = "DecayError";
+
+/// Writes the stack in the V8 format (`    at function (file:line:column)`), one entry per place.
+fn write_stack(stack: &mut String, message: &str, chain: Option<&CodePlaceChain>) -> FmtResult {
+    write!(stack, "{}: {}", JS_ERROR_NAME, message)?;
+    for place in chain.into_iter().flatten() {
+        write!(stack, "\n    at {}", place)?;
+    }
+    Ok(())
+}
+
+fn level_error<O: StdError>(level: &Decay<O>, cause: Option<JsError>) -> JsError {
+    let (message, chain) = match level {
+        Decay::External { error } => (error.to_string(), None),
+        Decay::Internal { note, place, .. } | Decay::Further { note, place, .. } => {
+            (note.text().unwrap_or_default().to_owned(), Some(place))
+        }
+    };
+    let error = JsError::new(&message);
+    error.set_name(JS_ERROR_NAME);
+    let mut stack = String::new();
+    // Properties of a fresh Error object are writable, so setting them can't fail.
+    if write_stack(&mut stack, &message, chain).is_ok() {
+        let _ = Reflect::set(&error, &"stack".into(), &stack.into());
+    }
+    if let Some(cause) = cause {
+        let _ = Reflect::set(&error, &"cause".into(), &cause);
+    }
+    error
+}
+
+impl<O: StdError> Decay<O> {
+    /// Converts the decay into JavaScript `Error` of the outermost level, whose `cause` chain
+    /// mirrors the inner levels. The `stack` of every error lists the places of its level.
+    pub fn to_js_error(&self) -> JsError {
+        let mut cause = None;
+        for level in self.iter_rev() {
+            cause = Some(level_error(level, cause));
+        }
+        cause.expect("Decay always has at least one level")
+    }
+}
+
+impl<O: StdError> From<Decay<O>> for JsError {
+    fn from(src: Decay<O>) -> Self {
+        src.to_js_error()
+    }
+}
+
+/// Makes the JavaScript error (see [Decay::to_js_error]), so `?` works in `#[wasm_bindgen]` functions.
+impl<O: StdError> From<Decay<O>> for JsValue {
+    fn from(src: Decay<O>) -> Self {
+        src.to_js_error().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodePlace;
+
+    #[cfg(target_arch = "wasm32")]
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error("Connection reset")]
+    struct TestErr;
+
+    #[cfg(target_arch = "wasm32")]
+    type Fail = Decay<TestErr>;
+
+    #[test]
+    fn stack_format() {
+        let chain = CodePlaceChain::from(
+            CodePlace::new("src/net.rs", 12, 5)
+                .with_module(Some("app::net"))
+                .with_function(Some("read")),
+        )
+        .prepend(CodePlace::new("src/client.rs", 40, 9));
+        let mut stack = String::new();
+        write_stack(&mut stack, "Can't read", Some(&chain)).expect("Writing should succeed");
+        assert_eq!(
+            stack,
+            "DecayError: Can't read\n    at src/client.rs:40:9\n    at app::net::read (src/net.rs:12:5)"
+        );
+        let mut stack = String::new();
+        write_stack(&mut stack, "Connection reset", None).expect("Writing should succeed");
+        assert_eq!(stack, "DecayError: Connection reset");
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn error_chain() {
+        let fail = Fail::from(TestErr)
+            .further(
+                CodePlace::new("src/net.rs", 12, 5),
+                "Can't read the response",
+            )
+            .further(CodePlace::new("src/main.rs", 7, 1), "Request failed");
+        let outer = JsError::from(fail);
+        assert_eq!(String::from(outer.name()), JS_ERROR_NAME);
+        assert_eq!(String::from(outer.message()), "Request failed");
+        let stack = Reflect::get(&outer, &"stack".into()).expect("Stack should be present");
+        assert_eq!(
+            stack.as_string().as_deref(),
+            Some("DecayError: Request failed\n    at src/main.rs:7:1")
+        );
+
+        let middle: JsError = Reflect::get(&outer, &"cause".into())
+            .expect("Cause should be present")
+            .into();
+        assert_eq!(String::from(middle.message()), "Can't read the response");
+        let root: JsError = Reflect::get(&middle, &"cause".into())
+            .expect("Cause should be present")
+            .into();
+        assert_eq!(String::from(root.message()), "Connection reset");
+        assert!(Reflect::get(&root, &"cause".into())
+            .expect("Property access should succeed")
+            .is_undefined());
+    }
+}
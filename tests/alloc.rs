@@ -0,0 +1,102 @@
+// The counting allocator is installed for the whole test binary, so these checks live in a binary of their own.
+
+use cadom::{place, rot, Decay, Note};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::Arc,
+};
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+enum FailKind {
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl From<String> for FailKind {
+    fn from(src: String) -> Self {
+        FailKind::Custom(src)
+    }
+}
+
+type Fail = Decay<FailKind>;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during<T>(action: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = action();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn rot_success_path_does_not_allocate() {
+    let value = 42u8;
+    let text = "Some text";
+
+    let (result, allocations) =
+        allocations_during(|| Ok::<u8, String>(value).map_err(rot!("Formatted note #{}", text)));
+    let result: Result<u8, Fail> = result;
+    assert_eq!(result, Ok(value));
+    assert_eq!(allocations, 0);
+
+    let (result, allocations) = allocations_during(|| Ok::<u8, String>(value).map_err(rot!()));
+    let result: Result<u8, Fail> = result;
+    assert_eq!(result, Ok(value));
+    assert_eq!(allocations, 0);
+
+    let (result, allocations) = allocations_during(|| {
+        Err::<u8, FailKind>(FailKind::Custom(String::new()))
+            .map_err(rot!("Formatted note #{}", text))
+    });
+    let result: Result<u8, Fail> = result;
+    assert!(allocations > 0);
+    match result {
+        Err(Decay::Further { note, .. }) => assert_eq!(note, "Formatted note #Some text"),
+        _ => panic!("Result should be Err(Decay::Further)"),
+    }
+}
+
+#[test]
+fn clone_shares_note_texts() {
+    let root = || Fail::from(FailKind::Custom(String::from("Root error")));
+    let owned = root()
+        .further(place!(), String::from("Owned note"))
+        .further(place!(), Arc::<str>::from("Shared note"))
+        .further_unnoted(place!());
+    let fixed = root()
+        .further(place!(), "Owned note")
+        .further(place!(), "Shared note")
+        .further_unnoted(place!());
+
+    let (cloned, owned_allocations) = allocations_during(|| owned.clone());
+    let (_, fixed_allocations) = allocations_during(|| fixed.clone());
+    assert_eq!(owned_allocations, fixed_allocations);
+    assert_eq!(cloned, owned);
+}
+
+#[test]
+fn arc_notes_share_texts() {
+    let text = Arc::<str>::from("Shared note");
+    let (note, allocations) = allocations_during(|| Note::from(text.clone()));
+    assert_eq!(allocations, 0);
+    assert_eq!(note, "Shared note");
+}
@@ -0,0 +1,45 @@
+use std::{env, fs, path::PathBuf};
+
+/// Checks the committed `include/cadom.h` against the one generated from `src/ffi.rs`
+/// (rewriting it instead if `CADOM_UPDATE_HEADER` is set) and compiles the C harness against it.
+fn main() {
+    let harness_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("Cargo sets it"));
+    let root_dir = harness_dir.join("..").join("..");
+    let source = root_dir.join("src").join("ffi.rs");
+    let include_dir = root_dir.join("include");
+    let header = include_dir.join("cadom.h");
+    let harness = harness_dir.join("harness.c");
+    println!("cargo:rerun-if-changed={}", source.display());
+    println!("cargo:rerun-if-changed={}", header.display());
+    println!("cargo:rerun-if-changed={}", harness.display());
+    println!("cargo:rerun-if-env-changed=CADOM_UPDATE_HEADER");
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("CADOM_H".into()),
+        documentation: true,
+        usize_is_size_t: true,
+        ..Default::default()
+    };
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(&source)
+        .generate()
+        .expect("C header should be generated")
+        .write(&mut generated);
+    if env::var_os("CADOM_UPDATE_HEADER").is_some() {
+        fs::write(&header, &generated).expect("C header should be written");
+    }
+    let committed = fs::read(&header).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "include/cadom.h is outdated, regenerate it with `CADOM_UPDATE_HEADER=1 cargo test -p cadom-ffi-harness`"
+    );
+
+    cc::Build::new()
+        .file(&harness)
+        .include(&include_dir)
+        .warnings_into_errors(true)
+        .compile("cadom_ffi_harness");
+}
@@ -0,0 +1,101 @@
+/* Exercises the C API of cadom; linked into the test suite of the `cadom-ffi-harness` crate. */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+#include "cadom.h"
+
+#define CHECK(code, condition) \
+    do {                       \
+        if (!(condition)) {    \
+            return (code);     \
+        }                      \
+    } while (0)
+
+typedef struct {
+    size_t count;
+    char texts[4][64];
+} Notes;
+
+typedef struct {
+    size_t count;
+    size_t levels[4];
+    uint32_t lines[4];
+    char files[4][64];
+} Places;
+
+static void copy_text(char *target, CadomStr text) {
+    size_t len = text.len < 63 ? text.len : 63;
+    if (text.ptr == NULL) {
+        strcpy(target, "(none)");
+        return;
+    }
+    memcpy(target, text.ptr, len);
+    target[len] = '\0';
+}
+
+static void collect_note(void *context, size_t level, CadomStr note) {
+    Notes *notes = context;
+    if (level == notes->count && notes->count < 4) {
+        copy_text(notes->texts[notes->count++], note);
+    }
+}
+
+static void collect_place(void *context, size_t level, CadomStr file, uint32_t line, uint32_t column) {
+    Places *places = context;
+    (void)column;
+    if (places->count < 4) {
+        places->levels[places->count] = level;
+        places->lines[places->count] = line;
+        copy_text(places->files[places->count++], file);
+    }
+}
+
+int cadom_ffi_harness(void) {
+    CadomDecay *decay = cadom_decay_new("plugin.cpp", 10, 5, "Can't open config");
+    CHECK(1, decay != NULL);
+    CHECK(2, cadom_decay_depth(decay) == 1);
+
+    decay = cadom_decay_further(decay, "host.cpp", 20, 1, NULL);
+    CHECK(3, cadom_decay_depth(decay) == 1);
+    decay = cadom_decay_further(decay, "main.cpp", 30, 3, "Plugin failed");
+    CHECK(4, cadom_decay_depth(decay) == 2);
+
+    Notes notes = {0};
+    cadom_decay_visit_notes(decay, collect_note, &notes);
+    CHECK(5, notes.count == 2);
+    CHECK(6, strcmp(notes.texts[0], "Plugin failed") == 0);
+    CHECK(7, strcmp(notes.texts[1], "Can't open config") == 0);
+
+    Places places = {0};
+    cadom_decay_visit_places(decay, collect_place, &places);
+    CHECK(8, places.count == 3);
+    CHECK(9, places.levels[0] == 0 && places.lines[0] == 30 && strcmp(places.files[0], "main.cpp") == 0);
+    CHECK(10, places.levels[1] == 1 && places.lines[1] == 20 && strcmp(places.files[1], "host.cpp") == 0);
+    CHECK(11, places.levels[2] == 1 && places.lines[2] == 10 && strcmp(places.files[2], "plugin.cpp") == 0);
+
+    char *rendered = cadom_decay_render(decay);
+    CHECK(12, rendered != NULL);
+    CHECK(13, strstr(rendered, "note: Plugin failed") != NULL);
+    CHECK(14, strstr(rendered, "note: Can't open config") != NULL);
+    cadom_string_free(rendered);
+    cadom_decay_free(decay);
+
+    decay = cadom_decay_from_error("Socket closed");
+    decay = cadom_decay_further(decay, "net.cpp", 7, 2, "Request failed");
+    notes.count = 0;
+    cadom_decay_visit_notes(decay, collect_note, &notes);
+    CHECK(15, notes.count == 2);
+    CHECK(16, strcmp(notes.texts[1], "Socket closed") == 0);
+    cadom_decay_visit_notes(decay, NULL, &notes);
+    cadom_decay_visit_places(decay, NULL, &places);
+    cadom_decay_free(decay);
+
+    CHECK(17, cadom_decay_depth(NULL) == 0);
+    CHECK(18, cadom_decay_render(NULL) == NULL);
+    CHECK(19, cadom_decay_further(NULL, "none.cpp", 1, 1, NULL) == NULL);
+    cadom_decay_free(NULL);
+    cadom_string_free(NULL);
+    return 0;
+}
@@ -0,0 +1,24 @@
+//! Links the C harness exercising the C API of cadom (see `harness.c`).
+
+use std::ffi::c_int;
+
+// The C API is defined in cadom, which is linked only when referenced.
+use cadom as _;
+
+#[link(name = "cadom_ffi_harness", kind = "static")]
+extern "C" {
+    fn cadom_ffi_harness() -> c_int;
+}
+
+/// Runs the harness, returning zero on success or the number of the failed check.
+pub fn run() -> c_int {
+    unsafe { cadom_ffi_harness() }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn c_harness() {
+        assert_eq!(super::run(), 0);
+    }
+}
@@ -0,0 +1,20 @@
+// The process-wide format is shared by the whole test binary, so it is checked in a binary of its own.
+
+use cadom::{CodePlace, Decay, DecayFormat};
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[error("{0}")]
+struct TestErr(&'static str);
+
+type Fail = Decay<TestErr>;
+
+#[test]
+fn global_format() {
+    let fail = Fail::from(TestErr("External error"))
+        .further(CodePlace::new("src/lib.rs", 38, 55), "Outer note");
+    assert_eq!(format!("{}", fail.display_global()), format!("{:?}", fail));
+    DecayFormat::set_global(DecayFormat::new().with_notes_only(true));
+    assert_eq!(format!("{}", fail.display_global()), "{note: Outer note}");
+    DecayFormat::set_global(DecayFormat::new());
+    assert_eq!(format!("{}", fail.display_global()), format!("{:?}", fail));
+}
@@ -0,0 +1,90 @@
+// The panic hook is shared by the whole test binary, so hooks are checked in a binary of their own.
+
+use std::{
+    panic::{self, UnwindSafe},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use cadom::{catch, Decay, DecayFormat, DecayRoot, PanicHook, PanicOrigin};
+
+/// Tests replacing the panic hook should not run concurrently.
+fn hook_lock() -> MutexGuard<'static, ()> {
+    static HOOK: Mutex<()> = Mutex::new(());
+    HOOK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Panics with the temporary hook set and returns the report the hook has made.
+fn report_of<F: FnOnce() + UnwindSafe>(hook: PanicHook, action: F) -> String {
+    let _lock = hook_lock();
+    let report = Arc::new(Mutex::new(String::new()));
+    let written = report.clone();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        *written.lock().expect("Report should be available") = hook.render(info)
+    }));
+    let result = panic::catch_unwind(action);
+    panic::set_hook(previous);
+    assert!(result.is_err());
+    let report = report.lock().expect("Report should be available");
+    report.clone()
+}
+
+#[test]
+fn formatted_panic() {
+    let format = DecayFormat::new().with_threads(false);
+    let line = line!() + 2;
+    let report = report_of(PanicHook::new().with_format(format.clone()), || {
+        panic!("Value {} is out of range", 42)
+    });
+    assert_eq!(
+        report,
+        format!(
+            "{{place: [tests/panic_hook.rs:{}:9], note: Value 42 is out of range}}",
+            line
+        )
+    );
+    let report = report_of(PanicHook::new().with_format(format), || {
+        std::panic::panic_any(42u8)
+    });
+    assert!(report.starts_with("{place: [tests/panic_hook.rs:"));
+    assert!(!report.contains("note"));
+}
+
+#[test]
+fn caught_panics_reach_installed_hook() {
+    let _lock = hook_lock();
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let written = reported.clone();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let decay = Decay::<PanicOrigin>::from_panic(info);
+        let mut reported = written.lock().expect("Reports should be available");
+        reported.push(decay.to_string())
+    }));
+    let line = line!() + 3;
+    let result: Result<(), Decay<PanicOrigin>> = catch(|| {
+        assert!(panic::catch_unwind(|| panic!("Handled panic")).is_err());
+        panic!("Escaped panic")
+    });
+    panic::set_hook(previous);
+    let fail = result.expect_err("Panic should be caught");
+    match fail.root() {
+        DecayRoot::Internal { place, .. } => {
+            assert_eq!(place.iter().next().map(|place| place.line), Some(line))
+        }
+        DecayRoot::External { .. } => panic!("Expected DecayRoot::Internal"),
+    }
+    assert_eq!(
+        *reported.lock().expect("Reports should be available"),
+        ["{note: Handled panic}", "{note: Escaped panic}"]
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_panic() {
+    let report = report_of(PanicHook::new().with_json(true), || {
+        panic!("Static message")
+    });
+    assert_eq!(report, r#"["Static message"]"#);
+}
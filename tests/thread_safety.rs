@@ -0,0 +1,5 @@
+#[test]
+fn non_thread_safe_origins() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
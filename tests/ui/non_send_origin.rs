@@ -0,0 +1,11 @@
+use std::rc::Rc;
+
+use cadom::SyncDecay;
+
+#[derive(thiserror::Error, Debug)]
+#[error("Thread-bound error")]
+struct LocalErr(Rc<u8>);
+
+fn main() {
+    let _: SyncDecay<LocalErr> = LocalErr(Rc::new(0)).into();
+}